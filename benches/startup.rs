@@ -0,0 +1,56 @@
+//! Compares how long a generated CLI with thousands of commands takes to parse one command line,
+//! and to build its whole command tree as help and completions do, with and without `lazy`. The
+//! CLIs are scratch crates built in release mode, so a run takes a few minutes:
+//! `cargo bench --bench startup`.
+mod support;
+
+use std::process::Command;
+
+use support::Scratch;
+
+const PATHS: usize = 3000;
+const ENUMS: usize = 10;
+
+// Reports the average nanoseconds to parse one command line, then to build the full tree
+const MAIN: &str = r#"fn main() {
+    use clap::CommandFactory;
+    use std::{hint::black_box, time::Instant};
+
+    const ITERATIONS: u32 = 50;
+    let args = ["prog", "group3", "resource3", "action3", "--id", "x"];
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(Cli::try_parse_from(args).unwrap());
+    }
+    let parse = start.elapsed() / ITERATIONS;
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let mut cmd = Cli::command();
+        cmd.build();
+        black_box(cmd);
+    }
+    let build = start.elapsed() / ITERATIONS;
+    println!("{} {}", parse.as_nanos(), build.as_nanos());
+}
+"#;
+
+fn main() {
+    if !support::benchmarking() {
+        return;
+    }
+
+    println!("{} paths in {} subcommand enums", PATHS, ENUMS);
+    println!("{:<24} {:>12} {:>16}", "trie options", "parse one", "build full tree");
+    for (name, attrs) in [("eager", ""), ("lazy", "lazy")] {
+        let scratch = Scratch::new(&format!("startup-{}", name), &support::cli_source(PATHS, ENUMS, attrs, MAIN));
+        scratch.build("release");
+
+        let output = Command::new(scratch.binary("release")).output().expect("Unable to run the scratch CLI");
+        let output = String::from_utf8(output.stdout).unwrap();
+        let (parse, build) = output.trim().split_once(' ').unwrap();
+        let micros = |nanos: &str| format!("{:.1} µs", nanos.parse::<f64>().unwrap() / 1000.0);
+        println!("{:<24} {:>12} {:>16}", if attrs.is_empty() { "(none)" } else { attrs }, micros(parse), micros(build));
+    }
+}
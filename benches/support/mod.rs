@@ -0,0 +1,89 @@
+//! Scratch crates with a generated CLI, for benchmarks which need a real build of the macros'
+//! output rather than the trie alone.
+#![allow(dead_code)]
+
+use std::{fmt::Write, fs, path::{Path, PathBuf}, process::Command};
+
+/// Source of a CLI with `paths` commands spread over `enums` subcommand enums, shaped like a large
+/// real one: `group{enum} resource{n} action{path}`, each taking an optional `--id`. `trie_attrs`
+/// goes inside the trie enum's `#[trie(...)]`, and `main` is appended as is.
+pub fn cli_source(paths: usize, enums: usize, trie_attrs: &str, main: &str) -> String {
+    let mut out = String::new();
+    writeln!(out, "use clap::Parser;\n\nmod commands {{\n    use clap_trie::clap_subcommand;").unwrap();
+    for group in 0..enums {
+        writeln!(out, "    clap_subcommand!{{\n        enum Group{} {{", group).unwrap();
+        for i in (group..paths).step_by(enums) {
+            writeln!(out, "            \"group{} resource{} action{}\" => {{ #[arg(long)] pub id: Option<String> }},", group, i % 100, i).unwrap();
+        }
+        writeln!(out, "        }}\n    }}").unwrap();
+    }
+    writeln!(out, "}}\n\nclap_trie::clap_trie!{{\n    #[trie({})]\n    enum Commands {{", trie_attrs).unwrap();
+    for group in 0..enums {
+        writeln!(out, "        commands::Group{},", group).unwrap();
+    }
+    writeln!(out, "    }}\n}}\n").unwrap();
+    out.push_str(r#"#[derive(clap::Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+"#);
+    out.push_str(main);
+    out
+}
+
+/// A binary crate under the bench's scratch directory, depending on clap and this crate.
+pub struct Scratch {
+    pub name: String,
+    pub dir: PathBuf,
+    pub target_dir: PathBuf,
+}
+
+impl Scratch {
+    /// Writes the crate, along with this crate's lock file so it builds against the same
+    /// dependencies. Every scratch crate shares a target directory, so they're only built once.
+    pub fn new(name: &str, source: &str) -> Self {
+        let root = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("scratch");
+        let dir = root.join(name);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("Cargo.toml"), format!(r#"[package]
+name = "{}"
+version = "0.0.0"
+edition = "2021"
+
+[dependencies]
+clap = {{ version = "4", features = ["derive"] }}
+clap-trie = {{ path = {:?} }}
+
+[workspace]
+"#, name, env!("CARGO_MANIFEST_DIR"))).unwrap();
+        fs::write(dir.join("src/main.rs"), source).unwrap();
+        if let Ok(lock) = fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.lock")) {
+            fs::write(dir.join("Cargo.lock"), lock).unwrap();
+        }
+        Self { name: name.to_string(), dir, target_dir: root.join("target") }
+    }
+
+    /// Builds the crate with a cargo profile.
+    pub fn build(&self, profile: &str) {
+        let status = Command::new(env!("CARGO"))
+            .args(["build", "--quiet", "--profile", profile, "--target-dir"])
+            .arg(&self.target_dir)
+            .current_dir(&self.dir)
+            .status()
+            .expect("Unable to run cargo");
+        assert!(status.success(), "Building {} failed", self.dir.display());
+    }
+
+    /// Path to the binary built with a cargo profile.
+    pub fn binary(&self, profile: &str) -> PathBuf {
+        let profile_dir = if profile == "dev" { "debug" } else { profile };
+        self.target_dir.join(profile_dir).join(format!("{}{}", self.name, std::env::consts::EXE_SUFFIX))
+    }
+}
+
+/// `cargo test --benches` runs benches without --bench, where these shouldn't spend minutes building.
+pub fn benchmarking() -> bool {
+    std::env::args().any(|arg| arg == "--bench")
+}
@@ -0,0 +1,79 @@
+//! Compares the plain and radix-compressed trie layouts on a synthetic command set.
+use std::{alloc::{GlobalAlloc, Layout, System}, hint::black_box, sync::atomic::{AtomicUsize, Ordering}};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use clap_trie_core::{Position, RadixTrie, Trie};
+
+// Tracks the bytes currently allocated, to compare how much each layout keeps on the heap
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+// Bytes still allocated once build returns, including the keys themselves
+fn heap_size<T>(build: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let built = build();
+    (built, ALLOCATED.load(Ordering::Relaxed) - before)
+}
+
+// Paths shaped like a large CLI: a few groups, many resources, and deep single child chains
+fn paths(count: usize) -> Vec<Vec<String>> {
+    (0..count).map(|i| {
+        format!("group{} resource{} action{} now", i % 20, i % 250, i)
+            .split(' ')
+            .map(str::to_string)
+            .collect()
+    }).collect()
+}
+
+fn count_nodes(_: Option<&usize>, _: Position<'_, String>, memo: Vec<usize>) -> usize {
+    1 + memo.into_iter().sum::<usize>()
+}
+
+fn bench_layouts(c: &mut Criterion) {
+    for count in [100, 5000] {
+        let paths = paths(count);
+        let entries = || paths.iter().cloned().enumerate().map(|(i, path)| (path, i));
+        let (trie, trie_size) = heap_size(|| entries().collect::<Trie<_, _>>());
+        let (radix, radix_size) = heap_size(|| entries().collect::<RadixTrie<_, _>>());
+        println!("heap/{}: trie {} bytes, radix {} bytes", count, trie_size, radix_size);
+
+        let mut group = c.benchmark_group("build");
+        group.bench_function(BenchmarkId::new("trie", count), |b| b.iter(|| entries().collect::<Trie<_, _>>()));
+        group.bench_function(BenchmarkId::new("radix", count), |b| b.iter(|| entries().collect::<RadixTrie<_, _>>()));
+        group.finish();
+
+        let mut group = c.benchmark_group("lookup");
+        group.bench_function(BenchmarkId::new("trie", count), |b| b.iter(|| {
+            paths.iter().filter_map(|path| trie.lookup(black_box(path))).count()
+        }));
+        group.bench_function(BenchmarkId::new("radix", count), |b| b.iter(|| {
+            paths.iter().filter_map(|path| radix.lookup(black_box(path))).count()
+        }));
+        group.finish();
+
+        let mut group = c.benchmark_group("aggregate");
+        group.bench_function(BenchmarkId::new("trie", count), |b| b.iter(|| trie.aggregate(&mut count_nodes)));
+        group.bench_function(BenchmarkId::new("radix", count), |b| b.iter(|| radix.aggregate(&mut count_nodes)));
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_layouts);
+criterion_main!(benches);
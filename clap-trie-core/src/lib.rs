@@ -0,0 +1,21 @@
+//! The trie clap-trie's macros build their command tree with, for use at runtime. Paths are
+//! sequences of segments, such as a command's words:
+//! ```
+//! use clap_trie_core::{Trie, TrieKey};
+//!
+//! let mut trie = Trie::new();
+//! trie.insert("get person".iter_keys(), 1);
+//! trie.insert("get place".iter_keys(), 2);
+//! assert_eq!(trie.lookup(["get", "place"]), Some(&2));
+//! assert_eq!(trie.iter_prefix(["get"]).count(), 2);
+//! ```
+#[cfg(feature = "radix")]
+mod radix_trie;
+mod trie;
+mod trie_key;
+
+#[cfg(feature = "radix")]
+pub use radix_trie::RadixTrie;
+
+pub use trie::{Entry, IntoIter, Iter, OccupiedEntry, Position, Trie, TrieNode, VacantEntry, VisitingAggregator, Walk};
+pub use trie_key::TrieKey;
@@ -0,0 +1,498 @@
+use std::{borrow::Borrow, convert::Infallible, fmt, ops::ControlFlow};
+
+use smallvec::SmallVec;
+
+use crate::{Position, Trie, VisitingAggregator, Walk};
+
+/// A trie with the same behaviour as [`Trie`], but which merges chains of single child nodes into
+/// one edge labelled with all of their segments. Children are kept in a sorted small vector rather
+/// than a map, as most nodes only have a few.
+///
+/// Traversals still visit every segment of a merged chain, so visitors see the same nodes they
+/// would in a [`Trie`].
+pub struct RadixTrie<K, V> {
+    root: RadixNode<K, V>,
+}
+
+// An edge's label, the segments it covers, and the node at its end. Keeping the first segment
+// inline saves following a pointer for every comparison while searching children.
+type Edge<K, V> = (SmallVec<[K; 1]>, Box<RadixNode<K, V>>);
+
+struct RadixNode<K, V> {
+    children: SmallVec<[Edge<K, V>; 2]>, // Sorted by the first segment of the label
+    value: Option<V>,
+}
+
+impl<K, V> Default for RadixNode<K, V> {
+    fn default() -> Self {
+        RadixNode {
+            children: SmallVec::new(),
+            value: None,
+        }
+    }
+}
+
+impl<K, V> Default for RadixTrie<K, V> {
+    fn default() -> Self {
+        Self { root: RadixNode::default() }
+    }
+}
+
+impl<K: Clone, V: Clone> Clone for RadixNode<K, V> {
+    fn clone(&self) -> Self {
+        RadixNode {
+            children: self.children.clone(),
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<K: Clone, V: Clone> Clone for RadixTrie<K, V> {
+    fn clone(&self) -> Self {
+        RadixTrie { root: self.root.clone() }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for RadixNode<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("RadixNode");
+        builder.field("value", &self.value);
+        builder.field("children", &self.children);
+        builder.finish()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for RadixTrie<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("RadixTrie");
+        builder.field("root", &self.root);
+        builder.finish()
+    }
+}
+
+impl<K: Ord, V> RadixTrie<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Finds path, returning the rest of the edge it ends partway along, if any, and the node at
+    // the end of that edge
+    fn cursor<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<(&[K], &RadixNode<K, V>)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        let mut target = &self.root;
+        let mut rest: &[K] = &[];
+        for part in path {
+            match rest.split_first() {
+                Some((key, tail)) if key.borrow() == part => rest = tail,
+                Some(_) => return None,
+                None => {
+                    let index = Self::find_child(target, part)?;
+                    let (label, child) = &target.children[index];
+                    rest = &label[1..];
+                    target = child;
+                }
+            }
+        }
+        Some((rest, target))
+    }
+
+    // Most nodes have a few children, where comparing for equality is cheaper than ordering
+    fn find_child<Q>(node: &RadixNode<K, V>, part: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        if node.children.len() <= 32 {
+            node.children.iter().position(|(label, _)| label[0].borrow() == part)
+        } else {
+            node.children.binary_search_by(|(label, _)| label[0].borrow().cmp(part)).ok()
+        }
+    }
+
+    // Children of path, as (rest of edge label, node at the end of the edge). A position partway
+    // along an edge has the rest of that edge as its only child.
+    fn edges<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<impl Iterator<Item = (&[K], &RadixNode<K, V>)>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        let (rest, target) = self.cursor(path)?;
+        let (children, pending) = match rest.is_empty() {
+            true => (&target.children[..], None),
+            false => (&[][..], Some((rest, target))),
+        };
+        Some(children.iter().map(|(label, child)| (&label[..], &**child)).chain(pending))
+    }
+
+    pub fn lookup<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        match self.cursor(path)? {
+            ([], target) => target.value.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// See [`Trie::longest_prefix`].
+    pub fn longest_prefix<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<(usize, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        let mut longest = self.root.value.as_ref().map(|value| (0, value));
+        let mut target = &self.root;
+        let mut rest: &[K] = &[];
+        for (depth, part) in path.into_iter().enumerate() {
+            match rest.split_first() {
+                Some((key, tail)) if key.borrow() == part => rest = tail,
+                Some(_) => break,
+                None => {
+                    let Some(index) = Self::find_child(target, part) else { break };
+                    let (label, child) = &target.children[index];
+                    rest = &label[1..];
+                    target = child;
+                }
+            }
+            // Values only sit at the ends of edges
+            if let (true, Some(value)) = (rest.is_empty(), &target.value) {
+                longest = Some((depth + 1, value));
+            }
+        }
+        longest
+    }
+
+    /// Whether any path in the trie starts with `path`, including `path` itself.
+    pub fn contains_prefix<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        // Inserting never leaves empty nodes, so any position leads to a value
+        self.cursor(path).is_some_and(|(rest, target)| !rest.is_empty() || target.value.is_some() || !target.children.is_empty())
+    }
+
+    pub fn children<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<impl Iterator<Item=(&K, &V)>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        Some(self.edges(path)?.filter_map(|(label, child)| match label {
+            [key] => Some((key, child.value.as_ref()?)),
+            _ => None,
+        }))
+    }
+
+    pub fn child_values<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<impl Iterator<Item=&V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        Some(self.children(path)?.map(|(_, value)| value))
+    }
+
+    pub fn child_keys<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<impl Iterator<Item=&K>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        Some(self.edges(path)?.map(|(label, _)| &label[0]))
+    }
+
+    pub fn root_value(&self) -> Option<&V> {
+        self.root.value.as_ref()
+    }
+
+    /// Number of values in the trie.
+    pub fn len(&self) -> usize {
+        fn count<K, V>(node: &RadixNode<K, V>) -> usize {
+            usize::from(node.value.is_some()) + node.children.iter().map(|(_, child)| count(child)).sum::<usize>()
+        }
+        count(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.value.is_none() && self.root.children.is_empty()
+    }
+
+    pub fn insert(&mut self, path: impl IntoIterator<Item = K>, value: V) -> Option<V> {
+        Self::insert_recurse(&mut self.root, path.into_iter().collect(), value)
+    }
+
+    fn insert_recurse(node: &mut RadixNode<K, V>, mut path: Vec<K>, value: V) -> Option<V> {
+        if path.is_empty() {
+            return node.value.replace(value);
+        }
+
+        match node.children.binary_search_by(|(label, _)| label[0].cmp(&path[0])) {
+            Err(index) => {
+                let child = RadixNode { children: SmallVec::new(), value: Some(value) };
+                node.children.insert(index, (SmallVec::from_vec(path), Box::new(child)));
+                None
+            }
+            Ok(index) => {
+                let (label, child) = &mut node.children[index];
+                let common = label.iter().zip(&path).take_while(|(a, b)| a == b).count();
+                // Split the edge where the paths diverge, so the new path can branch from there
+                if common < label.len() {
+                    let suffix = label.drain(common..).collect();
+                    let old = std::mem::take(&mut **child);
+                    child.children.push((suffix, Box::new(old)));
+                }
+                let rest = path.split_off(common);
+                Self::insert_recurse(child, rest, value)
+            }
+        }
+    }
+
+    /// See [`Trie::aggregate_depth_first_root`].
+    pub fn aggregate_depth_first_root<Agg, F: VisitingAggregator<K, V, Agg>>(&mut self, visitor: &mut F) -> Agg
+    {
+        let result = self.aggregate_depth_first(visitor);
+        visitor.visit(self.root.value.as_mut(), Position { path: &[] }, result)
+    }
+
+    /// See [`Trie::aggregate_depth_first`].
+    pub fn aggregate_depth_first<Agg, F: VisitingAggregator<K, V, Agg>>(&mut self, visitor: &mut F) -> Vec<Agg>
+    {
+        Self::visit_depth_recurse(&mut self.root, &[], visitor)
+    }
+
+    fn visit_depth_recurse<Agg, F: VisitingAggregator<K, V, Agg>>(node: &mut RadixNode<K, V>, path: &[&K], visitor: &mut F) -> Vec<Agg>
+    {
+        let mut results = vec![];
+        for (label, child) in node.children.iter_mut() {
+            let mut child_path = path.to_vec();
+            child_path.extend(label.iter());
+            let result = Self::visit_depth_recurse(child, &child_path, visitor);
+            let mut result = visitor.visit(child.value.as_mut(), Position { path: &child_path }, result);
+            // Nodes merged into the edge have no value and a single child
+            for depth in (path.len() + 1..child_path.len()).rev() {
+                result = visitor.visit(None, Position { path: &child_path[..depth] }, vec![result]);
+            }
+            results.push(result);
+        }
+        results
+    }
+
+    /// See [`Trie::aggregate`].
+    pub fn aggregate<Agg, F>(&self, visitor: &mut F) -> Agg
+    where
+        F: FnMut(Option<&V>, Position<'_, K>, Vec<Agg>) -> Agg
+    {
+        let result = self.try_aggregate(&mut |value, position, memo| Ok::<_, Infallible>(visitor(value, position, memo)));
+        match result {
+            Ok(agg) => agg,
+            Err(never) => match never {},
+        }
+    }
+
+    /// See [`Trie::try_aggregate`].
+    pub fn try_aggregate<Agg, E, F>(&self, visitor: &mut F) -> Result<Agg, E>
+    where
+        F: FnMut(Option<&V>, Position<'_, K>, Vec<Agg>) -> Result<Agg, E>
+    {
+        Self::try_aggregate_recurse(&self.root, &[], visitor)
+    }
+
+    fn try_aggregate_recurse<Agg, E, F>(node: &RadixNode<K, V>, path: &[&K], visitor: &mut F) -> Result<Agg, E>
+    where
+        F: FnMut(Option<&V>, Position<'_, K>, Vec<Agg>) -> Result<Agg, E>
+    {
+        let mut memo = Vec::with_capacity(node.children.len());
+        for (label, child) in &node.children {
+            let mut child_path = path.to_vec();
+            child_path.extend(label.iter());
+            let mut result = Self::try_aggregate_recurse(child, &child_path, visitor)?;
+            for depth in (path.len() + 1..child_path.len()).rev() {
+                result = visitor(None, Position { path: &child_path[..depth] }, vec![result])?;
+            }
+            memo.push(result);
+        }
+        visitor(node.value.as_ref(), Position { path }, memo)
+    }
+
+    /// See [`Trie::pre_order`].
+    pub fn pre_order(&self, mut visitor: impl FnMut(Option<&V>, Position<'_, K>) -> Walk) -> bool {
+        Self::walk_recurse(&self.root, &[], &mut visitor, true).is_continue()
+    }
+
+    /// See [`Trie::post_order`].
+    pub fn post_order(&self, mut visitor: impl FnMut(Option<&V>, Position<'_, K>) -> Walk) -> bool {
+        Self::walk_recurse(&self.root, &[], &mut visitor, false).is_continue()
+    }
+
+    fn walk_recurse<F>(node: &RadixNode<K, V>, path: &[&K], visitor: &mut F, pre_order: bool) -> ControlFlow<()>
+    where
+        F: FnMut(Option<&V>, Position<'_, K>) -> Walk
+    {
+        if pre_order {
+            match visitor(node.value.as_ref(), Position { path }) {
+                Walk::Continue => {}
+                Walk::Prune => return ControlFlow::Continue(()),
+                Walk::Stop => return ControlFlow::Break(()),
+            }
+        }
+        for (label, child) in &node.children {
+            let mut child_path = path.to_vec();
+            child_path.extend(label.iter());
+            Self::walk_edge(label.len(), child, &child_path, visitor, pre_order)?;
+        }
+        if !pre_order && visitor(node.value.as_ref(), Position { path }) == Walk::Stop {
+            return ControlFlow::Break(());
+        }
+        ControlFlow::Continue(())
+    }
+
+    // Walks the nodes merged into an edge of length merged + 1, then the node at its end
+    fn walk_edge<F>(length: usize, child: &RadixNode<K, V>, child_path: &[&K], visitor: &mut F, pre_order: bool) -> ControlFlow<()>
+    where
+        F: FnMut(Option<&V>, Position<'_, K>) -> Walk
+    {
+        if length == 1 {
+            return Self::walk_recurse(child, child_path, visitor, pre_order);
+        }
+
+        let path = &child_path[..child_path.len() + 1 - length];
+        if pre_order {
+            match visitor(None, Position { path }) {
+                Walk::Continue => {}
+                Walk::Prune => return ControlFlow::Continue(()),
+                Walk::Stop => return ControlFlow::Break(()),
+            }
+        }
+        Self::walk_edge(length - 1, child, child_path, visitor, pre_order)?;
+        if !pre_order && visitor(None, Position { path }) == Walk::Stop {
+            return ControlFlow::Break(());
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+impl<K: Ord + AsRef<str>, V> RadixTrie<K, V> {
+    pub fn child_keys_with_prefix<'a, 'q, Q>(&'a self, path: impl IntoIterator<Item = &'q Q>, prefix: &'a str) -> Option<impl Iterator<Item=&'a K>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        Some(self.child_keys(path)?.filter(move |child| child.as_ref().starts_with(prefix)))
+    }
+}
+
+impl<K: Ord, V, P: IntoIterator<Item = K>> Extend<(P, V)> for RadixTrie<K, V> {
+    fn extend<T: IntoIterator<Item=(P, V)>>(&mut self, iter: T) {
+        for item in iter {
+            self.insert(item.0, item.1);
+        }
+    }
+}
+
+impl<K: Ord, V, P: IntoIterator<Item = K>> FromIterator<(P, V)> for RadixTrie<K, V> {
+    fn from_iter<T: IntoIterator<Item=(P, V)>>(iter: T) -> Self {
+        let mut trie = Self::new();
+        trie.extend(iter);
+        trie
+    }
+}
+
+impl<K: Ord + Clone, V> From<Trie<K, V>> for RadixTrie<K, V> {
+    fn from(trie: Trie<K, V>) -> Self {
+        trie.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::RadixTrie;
+    use crate::{Trie, TrieKey, Walk};
+
+    fn joined(path: &[&String]) -> String {
+        path.iter().map(|key| key.as_str()).collect::<Vec<_>>().join(" ")
+    }
+
+    fn tries(paths: &[&str]) -> (Trie<String, String>, RadixTrie<String, String>) {
+        let entries = paths.iter().map(|path| (path.iter_keys().map(str::to_string), path.to_string()));
+        (entries.clone().collect(), entries.collect())
+    }
+
+    #[test]
+    fn test_lookup_and_children() {
+        let (_, trie) = tries(&["get person address", "get person", "get thing", "list groups all"]);
+        assert_eq!(trie.lookup(["get", "person"]).map(String::as_str), Some("get person"));
+        assert_eq!(trie.lookup(["list", "groups", "all"]).map(String::as_str), Some("list groups all"));
+        assert_eq!(trie.lookup(["list", "groups"]), None);
+        assert_eq!(trie.lookup(["list", "people"]), None);
+        assert_eq!(trie.len(), 4);
+
+        // Partway along the merged `list groups all` edge
+        assert_eq!(trie.child_keys(["list"]).map(|keys| keys.map(String::as_str).collect::<Vec<_>>()), Some(vec!["groups"]));
+        assert_eq!(trie.children(["list"]).map(Iterator::count), Some(0));
+        assert_eq!(trie.child_values(["list", "groups"]).map(|values| values.map(String::as_str).collect::<Vec<_>>()), Some(vec!["list groups all"]));
+        assert_eq!(trie.child_keys(["get"]).map(|keys| keys.map(String::as_str).collect::<Vec<_>>()), Some(vec!["person", "thing"]));
+        assert!(trie.child_keys(["missing"]).is_none());
+        assert!(trie.contains_prefix(["list", "groups"]));
+        assert!(!trie.contains_prefix(["list", "people"]));
+
+        assert_eq!(trie.longest_prefix(["list", "groups", "all", "--json"]).map(|(len, value)| (len, value.as_str())), Some((3, "list groups all")));
+        assert_eq!(trie.longest_prefix(["list", "groups", "some"]), None);
+        assert_eq!(trie.longest_prefix(["get", "person", "phone"]).map(|(len, _)| len), Some(2));
+    }
+
+    #[test]
+    fn test_traversals_match_trie() {
+        let paths = ["get person address", "get person", "get thing", "list groups all", "auth"];
+        let (trie, radix) = tries(&paths);
+
+        let visit = |value: Option<&String>, position: crate::Position<'_, String>, memo: Vec<String>| {
+            format!("{}={}[{}]", joined(position.path), value.map(String::as_str).unwrap_or("-"), memo.join(","))
+        };
+        assert_eq!(radix.aggregate(&mut { visit }), trie.aggregate(&mut { visit }));
+
+        let order = |pre_order: bool, radix_trie: bool| {
+            let mut visited = Vec::new();
+            let visitor = |_: Option<&String>, position: crate::Position<'_, String>| {
+                visited.push(joined(position.path));
+                if position.path == ["list", "groups"] { Walk::Prune } else { Walk::Continue }
+            };
+            match (pre_order, radix_trie) {
+                (true, true) => radix.pre_order(visitor),
+                (true, false) => trie.pre_order(visitor),
+                (false, true) => radix.post_order(visitor),
+                (false, false) => trie.post_order(visitor),
+            };
+            visited
+        };
+        assert_eq!(order(true, true), order(true, false));
+        assert_eq!(order(false, true), order(false, false));
+    }
+
+    proptest! {
+        #[test]
+        fn matches_trie(paths in prop::collection::vec(prop::collection::vec("[abc]", 0..5), 0..32)) {
+            let trie = paths.iter().map(|path| (path.clone(), path.join(" "))).collect::<Trie<_, _>>();
+            let radix = RadixTrie::from(trie.clone());
+
+            prop_assert_eq!(radix.len(), trie.len());
+            let visit = |value: Option<&String>, position: crate::Position<'_, String>, memo: Vec<String>| {
+                format!("{}={:?}[{}]", joined(position.path), value, memo.join(","))
+            };
+            prop_assert_eq!(radix.aggregate(&mut { visit }), trie.aggregate(&mut { visit }));
+
+            for path in &paths {
+                for prefix in 0..=path.len() {
+                    let path = &path[..prefix];
+                    prop_assert_eq!(radix.lookup(path), trie.lookup(path));
+                    prop_assert_eq!(radix.longest_prefix(path), trie.longest_prefix(path));
+                    prop_assert_eq!(radix.child_keys(path).map(Iterator::collect::<Vec<_>>), trie.child_keys(path).map(Iterator::collect::<Vec<_>>));
+                    prop_assert_eq!(radix.children(path).map(Iterator::collect::<Vec<_>>), trie.children(path).map(Iterator::collect::<Vec<_>>));
+                }
+            }
+        }
+    }
+}
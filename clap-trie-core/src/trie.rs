@@ -0,0 +1,959 @@
+use std::{borrow::Borrow, collections::BTreeMap, convert::Infallible, fmt, ops::ControlFlow};
+
+/// A trie keyed by paths of `K` segments. Paths are given as iterators of segments, for example
+/// `"get person".iter_keys()` or `[OsString::from("get")]`.
+///
+/// With the `serde` feature it serializes as its root node, see [`TrieNode`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent, bound(deserialize = "K: Ord + serde::Deserialize<'de>, V: serde::Deserialize<'de>")))]
+pub struct Trie<K, V> {
+    root: TrieNode<K, V>,
+}
+
+impl<K, V> Default for Trie<K, V> {
+    fn default() -> Self {
+        Self { root: TrieNode::default() }
+    }
+}
+
+impl<K: Clone, V: Clone> Clone for Trie<K, V> {
+    fn clone(&self) -> Self {
+        Trie { root: self.root.clone() }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Trie<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("Trie");
+        builder.field("root", &self.root);
+        builder.finish()
+    }
+}
+
+impl<K: Ord, V> Trie<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn node<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<&TrieNode<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        let mut current = &self.root;
+        for part in path {
+            current = current.children.get(part)?;
+        }
+        Some(current)
+    }
+
+    // Like node, but also returns the trie's own keys along the path
+    fn node_path<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<(Vec<&K>, &TrieNode<K, V>)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        let mut keys = Vec::new();
+        let mut current = &self.root;
+        for part in path {
+            let (key, child) = current.children.get_key_value(part)?;
+            keys.push(key);
+            current = child;
+        }
+        Some((keys, current))
+    }
+
+    pub fn lookup<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        self.node(path)?.value.as_ref()
+    }
+
+    pub fn get_mut<'q, Q>(&mut self, path: impl IntoIterator<Item = &'q Q>) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        let mut current = &mut self.root;
+        for part in path {
+            current = current.children.get_mut(part)?;
+        }
+        current.value.as_mut()
+    }
+
+    /// The longest stored path which `path` starts with, as the number of segments it spans and
+    /// its value. Segments after it, such as a command's arguments, are ignored.
+    pub fn longest_prefix<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<(usize, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        let mut longest = self.root.value.as_ref().map(|value| (0, value));
+        let mut current = &self.root;
+        for (depth, part) in path.into_iter().enumerate() {
+            let Some(child) = current.children.get(part) else { break };
+            if let Some(value) = &child.value {
+                longest = Some((depth + 1, value));
+            }
+            current = child;
+        }
+        longest
+    }
+
+    /// Whether any path in the trie starts with `path`, including `path` itself.
+    pub fn contains_prefix<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        self.node(path).is_some_and(|node| node.value.is_some() || !node.children.is_empty())
+    }
+
+    /// Number of values in the trie.
+    pub fn len(&self) -> usize {
+        fn count<K, V>(node: &TrieNode<K, V>) -> usize {
+            usize::from(node.value.is_some()) + node.children.values().map(count).sum::<usize>()
+        }
+        count(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        // Nodes are only created on the way to a value, and removing prunes any left without one
+        self.root.value.is_none() && self.root.children.is_empty()
+    }
+
+    /// Every `(path, value)` in the trie, depth first with siblings in key order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { stack: vec![(Vec::new(), &self.root)] }
+    }
+
+    /// Every `(path, value)` at or below `path`, in the same order as `iter`. Paths are full
+    /// paths, starting with `path`.
+    pub fn iter_prefix<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        Iter { stack: self.node_path(path).into_iter().collect() }
+    }
+
+    pub fn children<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<impl Iterator<Item=(&K, &V)>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        Some(self.node(path)?.children.iter().filter_map(|(k, v)| Some((k, v.value.as_ref()?))))
+    }
+
+    pub fn child_values<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<impl Iterator<Item=&V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        Some(self.node(path)?.children.values().filter_map(|v| v.value.as_ref()))
+    }
+
+    pub fn child_keys<'q, Q>(&self, path: impl IntoIterator<Item = &'q Q>) -> Option<impl Iterator<Item=&K>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        Some(self.node(path)?.children.keys())
+    }
+
+    pub fn root_value(&self) -> Option<&V> {
+        self.root.value.as_ref()
+    }
+
+    pub fn insert(&mut self, path: impl IntoIterator<Item = K>, value: V) -> Option<V> {
+        let mut current = &mut self.root;
+        for key in path {
+            current = current.children.entry(key).or_default();
+        }
+        current.value.replace(value)
+    }
+
+    /// Removes and returns the value at `path`, pruning any branches left without values.
+    pub fn remove<'q, Q>(&mut self, path: impl IntoIterator<Item = &'q Q>) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        let path = path.into_iter().collect::<Vec<_>>();
+        Self::remove_recurse(&mut self.root, &path)
+    }
+
+    fn remove_recurse<Q>(node: &mut TrieNode<K, V>, path: &[&Q]) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let Some((key, rest)) = path.split_first() else {
+            return node.value.take();
+        };
+
+        let child = node.children.get_mut(*key)?;
+        let value = Self::remove_recurse(child, rest);
+        if child.value.is_none() && child.children.is_empty() {
+            node.children.remove(*key);
+        }
+        value
+    }
+
+    /// Gets the entry at `path` for in place manipulation. Nodes are only created once a value
+    /// is inserted.
+    pub fn entry(&mut self, path: impl IntoIterator<Item = K>) -> Entry<'_, K, V> {
+        let mut path = path.into_iter().collect::<Vec<_>>().into_iter();
+        let mut current = &mut self.root;
+        let mut rest = Vec::new();
+        for key in path.by_ref() {
+            if !current.children.contains_key(&key) {
+                rest.push(key);
+                break;
+            }
+            current = current.children.get_mut(&key).unwrap();
+        }
+        rest.extend(path);
+
+        if rest.is_empty() && current.value.is_some() {
+            Entry::Occupied(OccupiedEntry { node: current })
+        } else {
+            Entry::Vacant(VacantEntry { node: current, rest })
+        }
+    }
+
+    /// Aggregates the trie bottom up, like `aggregate_depth_first`, then visits the root with the
+    /// aggregates of its children.
+    pub fn aggregate_depth_first_root<Agg, F: VisitingAggregator<K, V, Agg>>(&mut self, visitor: &mut F) -> Agg
+    {
+        let result = self.aggregate_depth_first(visitor);
+        visitor.visit(self.root.value.as_mut(), Position { path: &[] }, result)
+    }
+
+    /// Visits every node below the root bottom up, passing each the aggregates of its children,
+    /// and returns the aggregates of the root's children. Siblings are visited in key order.
+    pub fn aggregate_depth_first<Agg, F: VisitingAggregator<K, V, Agg>>(&mut self, visitor: &mut F) -> Vec<Agg>
+    {
+        Self::visit_depth_recurse(&mut self.root, &[], visitor)
+    }
+
+    fn visit_depth_recurse<Agg, F: VisitingAggregator<K, V, Agg>>(node: &mut TrieNode<K, V>, path: &[&K], visitor: &mut F) -> Vec<Agg>
+    {
+        let mut results = vec![];
+        for (key, child) in node.children.iter_mut() {
+            let mut child_path = path.to_vec();
+            child_path.push(key);
+            let result = Self::visit_depth_recurse(child, &child_path, visitor);
+            results.push(visitor.visit(child.value.as_mut(), Position { path: &child_path }, result));
+        }
+        results
+    }
+
+    /// Aggregates the trie bottom up without mutating it, visiting the root last with the
+    /// aggregates of its children.
+    pub fn aggregate<Agg, F>(&self, visitor: &mut F) -> Agg
+    where
+        F: FnMut(Option<&V>, Position<'_, K>, Vec<Agg>) -> Agg
+    {
+        let result = self.try_aggregate(&mut |value, position, memo| Ok::<_, Infallible>(visitor(value, position, memo)));
+        match result {
+            Ok(agg) => agg,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Like `aggregate`, but the first error returned by the visitor stops the walk and is
+    /// returned.
+    pub fn try_aggregate<Agg, E, F>(&self, visitor: &mut F) -> Result<Agg, E>
+    where
+        F: FnMut(Option<&V>, Position<'_, K>, Vec<Agg>) -> Result<Agg, E>
+    {
+        Self::try_aggregate_recurse(&self.root, &[], visitor)
+    }
+
+    fn try_aggregate_recurse<Agg, E, F>(node: &TrieNode<K, V>, path: &[&K], visitor: &mut F) -> Result<Agg, E>
+    where
+        F: FnMut(Option<&V>, Position<'_, K>, Vec<Agg>) -> Result<Agg, E>
+    {
+        let mut memo = Vec::with_capacity(node.children.len());
+        for (key, child) in &node.children {
+            let mut child_path = path.to_vec();
+            child_path.push(key);
+            memo.push(Self::try_aggregate_recurse(child, &child_path, visitor)?);
+        }
+        visitor(node.value.as_ref(), Position { path }, memo)
+    }
+
+    /// Visits every node parents first, starting with the root, with siblings in key order.
+    /// Returns `false` if the visitor stopped the walk.
+    pub fn pre_order(&self, mut visitor: impl FnMut(Option<&V>, Position<'_, K>) -> Walk) -> bool {
+        Self::walk_recurse(&self.root, &[], &mut visitor, true).is_continue()
+    }
+
+    /// Visits every node children first, ending with the root, with siblings in key order.
+    /// Subtrees have already been visited, so `Walk::Prune` only continues the walk. Returns
+    /// `false` if the visitor stopped the walk.
+    pub fn post_order(&self, mut visitor: impl FnMut(Option<&V>, Position<'_, K>) -> Walk) -> bool {
+        Self::walk_recurse(&self.root, &[], &mut visitor, false).is_continue()
+    }
+
+    fn walk_recurse<F>(node: &TrieNode<K, V>, path: &[&K], visitor: &mut F, pre_order: bool) -> ControlFlow<()>
+    where
+        F: FnMut(Option<&V>, Position<'_, K>) -> Walk
+    {
+        if pre_order {
+            match visitor(node.value.as_ref(), Position { path }) {
+                Walk::Continue => {}
+                Walk::Prune => return ControlFlow::Continue(()),
+                Walk::Stop => return ControlFlow::Break(()),
+            }
+        }
+        for (key, child) in &node.children {
+            let mut child_path = path.to_vec();
+            child_path.push(key);
+            Self::walk_recurse(child, &child_path, visitor, pre_order)?;
+        }
+        if !pre_order && visitor(node.value.as_ref(), Position { path }) == Walk::Stop {
+            return ControlFlow::Break(());
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+impl<K: Ord + AsRef<str>, V> Trie<K, V> {
+    pub fn child_keys_with_prefix<'a, 'q, Q>(&'a self, path: impl IntoIterator<Item = &'q Q>, prefix: &'a str) -> Option<impl Iterator<Item=&'a K>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized + 'q,
+    {
+        Some(self.child_keys(path)?.filter(move |child| child.as_ref().starts_with(prefix)))
+    }
+
+    /// Stored paths with as many segments as `path`, where each segment is compared with the one
+    /// at the same depth and the edit distances add up to at most `max_distance`. Returned as
+    /// `(path, value, distance)`, closest first and then in key order.
+    pub fn fuzzy<'q>(&self, path: impl IntoIterator<Item = &'q str>, max_distance: usize) -> Vec<(Vec<&K>, &V, usize)> {
+        let path = path.into_iter().collect::<Vec<_>>();
+        let mut matches = Vec::new();
+        Self::fuzzy_recurse(&self.root, &path, &mut Vec::new(), max_distance, &mut matches);
+        // Stable, so equally close paths stay in the key order they were found in
+        matches.sort_by_key(|(_, _, distance)| *distance);
+        matches
+    }
+
+    fn fuzzy_recurse<'a>(node: &'a TrieNode<K, V>, path: &[&str], keys: &mut Vec<&'a K>, budget: usize, matches: &mut Vec<(Vec<&'a K>, &'a V, usize)>) {
+        let Some((segment, rest)) = path.split_first() else {
+            if let Some(value) = &node.value {
+                matches.push((keys.clone(), value, 0));
+            }
+            return;
+        };
+        for (key, child) in &node.children {
+            let distance = edit_distance(key.as_ref(), segment);
+            if distance > budget {
+                continue;
+            }
+            keys.push(key);
+            let found = matches.len();
+            Self::fuzzy_recurse(child, rest, keys, budget - distance, matches);
+            for (_, _, total) in &mut matches[found..] {
+                *total += distance;
+            }
+            keys.pop();
+        }
+    }
+}
+
+impl<K: Ord, V, P: IntoIterator<Item = K>> Extend<(P, V)> for Trie<K, V> {
+    fn extend<T: IntoIterator<Item=(P, V)>>(&mut self, iter: T) {
+        for item in iter {
+            self.insert(item.0, item.1);
+        }
+    }
+}
+
+impl<K: Ord, V, P: IntoIterator<Item = K>> FromIterator<(P, V)> for Trie<K, V> {
+    fn from_iter<T: IntoIterator<Item=(P, V)>>(iter: T) -> Self {
+        let mut trie = Self::new();
+        trie.extend(iter);
+        trie
+    }
+}
+
+impl<'a, K: Ord, V> IntoIterator for &'a Trie<K, V> {
+    type Item = (Vec<&'a K>, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: Clone, V> IntoIterator for Trie<K, V> {
+    type Item = (Vec<K>, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { stack: vec![(Vec::new(), self.root)] }
+    }
+}
+
+/// A value in the trie, which may not exist yet, see [`Trie::entry`].
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+pub struct OccupiedEntry<'a, K, V> {
+    node: &'a mut TrieNode<K, V>,
+}
+
+pub struct VacantEntry<'a, K, V> {
+    node: &'a mut TrieNode<K, V>, // Deepest existing node on the path
+    rest: Vec<K>, // Segments below node still to be created
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with(self, default: impl FnOnce() -> V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify(mut self, f: impl FnOnce(&mut V)) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    pub fn get(&self) -> &V {
+        self.node.value.as_ref().unwrap()
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self.node.value.as_mut().unwrap()
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        self.node.value.as_mut().unwrap()
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        self.node.value.replace(value).unwrap()
+    }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+    pub fn insert(self, value: V) -> &'a mut V {
+        let mut current = self.node;
+        for key in self.rest {
+            current = current.children.entry(key).or_default();
+        }
+        current.value.insert(value)
+    }
+}
+
+/// Borrowing iterator over a trie's `(path, value)` pairs, see [`Trie::iter`].
+pub struct Iter<'a, K, V> {
+    stack: Vec<(Vec<&'a K>, &'a TrieNode<K, V>)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (Vec<&'a K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (path, node) = self.stack.pop()?;
+            // Pushed in reverse so the smallest key is visited next
+            for (key, child) in node.children.iter().rev() {
+                let mut child_path = path.clone();
+                child_path.push(key);
+                self.stack.push((child_path, child));
+            }
+            if let Some(value) = &node.value {
+                return Some((path, value));
+            }
+        }
+    }
+}
+
+/// Owning iterator over a trie's `(path, value)` pairs, in the same order as [`Trie::iter`].
+pub struct IntoIter<K, V> {
+    stack: Vec<(Vec<K>, TrieNode<K, V>)>,
+}
+
+impl<K: Clone, V> Iterator for IntoIter<K, V> {
+    type Item = (Vec<K>, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (path, node) = self.stack.pop()?;
+            for (key, child) in node.children.into_iter().rev() {
+                let mut child_path = path.clone();
+                child_path.push(key);
+                self.stack.push((child_path, child));
+            }
+            if let Some(value) = node.value {
+                return Some((path, value));
+            }
+        }
+    }
+}
+
+/// With the `serde` feature a node serializes as a map with an optional `value` and its
+/// `children` keyed by segment, either of which is left out when empty. For example
+/// `{"children": {"get": {"children": {"person": {"value": 1}}}}}`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "K: Ord + serde::Deserialize<'de>, V: serde::Deserialize<'de>")))]
+pub struct TrieNode<K, V> {
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "BTreeMap::is_empty"))]
+    pub(crate) children: BTreeMap<K, TrieNode<K, V>>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub(crate) value: Option<V>,
+}
+
+impl<K, V> Default for TrieNode<K, V> {
+    fn default() -> Self {
+        TrieNode {
+            children: BTreeMap::new(),
+            value: None,
+        }
+    }
+}
+
+impl<K: Clone, V: Clone> Clone for TrieNode<K, V> {
+    fn clone(&self) -> Self {
+        TrieNode {
+            children: self.children.clone(),
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for TrieNode<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("TrieNode");
+        builder.field("value", &self.value);
+        builder.field("children", &self.children);
+        builder.finish()
+    }
+}
+
+/// What a walk does after visiting a node, see [`Trie::pre_order`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Walk {
+    /// Carry on into the node's children
+    Continue,
+    /// Skip the node's children, carrying on with its next sibling
+    Prune,
+    /// End the walk
+    Stop,
+}
+
+/// Where a visitor is in the trie.
+#[derive(Debug)]
+pub struct Position<'a, K> {
+    /// Segments from the root to the visited node, empty for the root
+    pub path: &'a [&'a K],
+}
+
+// Derived impls would require K: Clone
+impl<K> Clone for Position<'_, K> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K> Copy for Position<'_, K> {}
+
+impl<'a, K> Position<'a, K> {
+    /// The visited node's own segment, `None` for the root.
+    pub fn segment(&self) -> Option<&'a K> {
+        self.path.last().copied()
+    }
+
+    /// Number of segments below the root, 0 for the root.
+    pub fn depth(&self) -> usize {
+        self.path.len()
+    }
+}
+
+// Levenshtein distance between two strings, counted in chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+pub trait VisitingAggregator<K, V, Agg> {
+    fn visit(&mut self, value: Option<&mut V>, position: Position<'_, K>, memo: Vec<Agg>) -> Agg;
+}
+
+impl<F, K, V, Agg> VisitingAggregator<K, V, Agg> for F
+where
+    F: FnMut(Option<&mut V>, Position<'_, K>, Vec<Agg>) -> Agg
+{
+    fn visit(&mut self, value: Option<&mut V>, position: Position<'_, K>, memo: Vec<Agg>) -> Agg {
+        self(value, position, memo)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, ffi::OsString};
+
+    use proptest::prelude::*;
+
+    use super::{Entry, Trie, Walk};
+    use crate::TrieKey;
+
+    fn joined(path: &[&String]) -> String {
+        path.iter().map(|key| key.as_str()).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut trie = Trie::new();
+        trie.insert("auth".iter_keys(), "auth");
+        trie.insert("get device bundles".iter_keys(), "get device bundles");
+        trie.insert("get device".iter_keys(), "get device");
+
+        assert_eq!(trie.child_keys("".iter_keys()).map(|keys| keys.copied().collect::<Vec<_>>()), Some(vec!["auth", "get"]));
+        assert_eq!(trie.lookup(["auth"]), Some(&"auth"));
+        assert_eq!(trie.lookup("get device bundles".iter_keys()), Some(&"get device bundles"));
+        assert_eq!(trie.lookup(["get", "device"]), Some(&"get device"));
+        assert_eq!(trie.lookup(["get"]), None);
+    }
+
+    #[test]
+    fn test_child_keys_with_prefix() {
+        let mut trie = Trie::new();
+        trie.insert("get thingy".iter_keys().map(str::to_string), ());
+        trie.insert("get thing".iter_keys().map(str::to_string), ());
+        trie.insert("get other".iter_keys().map(str::to_string), ());
+        trie.insert("grant".iter_keys().map(str::to_string), ());
+
+        let keys = |path: &[&str], prefix| trie.child_keys_with_prefix(path.iter().copied(), prefix).map(|keys| keys.cloned().collect::<Vec<_>>());
+        assert_eq!(keys(&[], "g"), Some(vec!["get".to_string(), "grant".to_string()]));
+        assert_eq!(keys(&["get"], "th"), Some(vec!["thing".to_string(), "thingy".to_string()]));
+        assert_eq!(keys(&["get"], "o"), Some(vec!["other".to_string()]));
+        assert_eq!(keys(&["get"], "x"), Some(vec![]));
+        assert!(keys(&["missing"], "").is_none());
+    }
+
+    #[test]
+    fn test_iter() {
+        let trie = ["get person", "get", "auth", "get person address", "list"].into_iter()
+            .map(|path| (path.iter_keys(), path))
+            .collect::<Trie<_, _>>();
+        assert_eq!(trie.len(), 5);
+        assert!(!trie.is_empty());
+        assert!(Trie::<&str, ()>::new().is_empty());
+
+        let values = trie.iter().map(|(path, value)| (path.into_iter().copied().collect::<Vec<_>>().join(" "), *value)).collect::<Vec<_>>();
+        assert_eq!(values, [
+            ("auth".to_string(), "auth"),
+            ("get".to_string(), "get"),
+            ("get person".to_string(), "get person"),
+            ("get person address".to_string(), "get person address"),
+            ("list".to_string(), "list"),
+        ]);
+
+        let values = trie.iter_prefix(["get", "person"]).map(|(_, value)| *value).collect::<Vec<_>>();
+        assert_eq!(values, ["get person", "get person address"]);
+        assert_eq!(trie.iter_prefix(["missing"]).count(), 0);
+        assert_eq!((&trie).into_iter().count(), 5);
+
+        let values = trie.into_iter().map(|(path, _)| path.len()).collect::<Vec<_>>();
+        assert_eq!(values, [1, 1, 2, 3, 1]);
+    }
+
+    #[test]
+    fn test_get_mut_and_contains_prefix() {
+        let mut trie = Trie::new();
+        trie.insert("get person".iter_keys(), 1);
+
+        *trie.get_mut(["get", "person"]).unwrap() += 1;
+        assert_eq!(trie.lookup(["get", "person"]), Some(&2));
+        assert!(trie.get_mut(["get"]).is_none());
+
+        assert!(trie.contains_prefix(["get"]));
+        assert!(trie.contains_prefix(["get", "person"]));
+        assert!(trie.contains_prefix("".iter_keys()));
+        assert!(!trie.contains_prefix(["person"]));
+        assert!(!trie.contains_prefix(["get", "person", "address"]));
+    }
+
+    #[test]
+    fn test_fuzzy() {
+        let trie = ["get person", "get persons", "get place", "set person", "list people"].into_iter()
+            .map(|path| (path.iter_keys().map(str::to_string), path))
+            .collect::<Trie<_, _>>();
+        let fuzzy = |path: &str, max_distance| trie.fuzzy(path.iter_keys(), max_distance).into_iter()
+            .map(|(_, value, distance)| (*value, distance))
+            .collect::<Vec<_>>();
+
+        assert_eq!(fuzzy("get person", 0), vec![("get person", 0)]);
+        assert_eq!(fuzzy("get persn", 1), vec![("get person", 1)]);
+        assert_eq!(fuzzy("get persn", 2), vec![("get person", 1), ("get persons", 2), ("set person", 2)]);
+        // Distances add up across segments rather than over the joined path
+        assert_eq!(fuzzy("gt persn", 1), vec![]);
+        assert_eq!(fuzzy("gt persn", 2), vec![("get person", 2)]);
+        // Only paths with the same number of segments match
+        assert_eq!(fuzzy("get", 3), vec![]);
+        assert_eq!(fuzzy("list people all", 3), vec![]);
+
+        let (path, _, _) = &trie.fuzzy(["lst", "people"], 1)[0];
+        assert_eq!(path, &["list", "people"]);
+        assert_eq!(super::edit_distance("kitten", "sitting"), 3);
+        assert_eq!(super::edit_distance("", "abc"), 3);
+        assert_eq!(super::edit_distance("café", "cafe"), 1);
+    }
+
+    #[test]
+    fn test_longest_prefix() {
+        let trie = ["get person", "get person address", "list"].into_iter()
+            .map(|path| (path.iter_keys().map(str::to_string), path))
+            .collect::<Trie<_, _>>();
+
+        let argv = ["get", "person", "--name", "bob"];
+        assert_eq!(trie.longest_prefix(argv), Some((2, &"get person")));
+        assert_eq!(trie.longest_prefix(["get", "person", "address"]), Some((3, &"get person address")));
+        assert_eq!(trie.longest_prefix(["get", "person", "phone"]), Some((2, &"get person")));
+        assert_eq!(trie.longest_prefix(["list", "person"]), Some((1, &"list")));
+        assert_eq!(trie.longest_prefix(["get", "thing"]), None);
+        assert_eq!(trie.longest_prefix("".iter_keys()), None);
+
+        let mut trie = trie;
+        trie.insert([], "root");
+        assert_eq!(trie.longest_prefix(["get", "thing"]), Some((0, &"root")));
+    }
+
+    #[test]
+    fn test_other_segment_types() {
+        let mut trie = Trie::new();
+        trie.insert([OsString::from("get"), OsString::from("person")], 1);
+        assert_eq!(trie.lookup(&[OsString::from("get"), OsString::from("person")]), Some(&1));
+
+        let mut numbers = Trie::new();
+        numbers.insert([1u8, 2], "one two");
+        numbers.insert([1u8], "one");
+        assert_eq!(numbers.lookup(&[1, 2]), Some(&"one two"));
+        assert_eq!(numbers.child_values(&[1]).map(Iterator::collect::<Vec<_>>), Some(vec![&"one two"]));
+
+        let mut visited = Vec::new();
+        numbers.pre_order(|_, position| {
+            visited.push(position.path.iter().map(|key| **key).collect::<Vec<_>>());
+            Walk::Continue
+        });
+        assert_eq!(visited, [vec![], vec![1], vec![1, 2]]);
+    }
+
+    #[test]
+    fn test_aggregate_depth_first() {
+        let mut trie = Trie::new();
+        trie.insert("get person address".iter_keys().map(str::to_string), 1);
+        trie.insert("auth".iter_keys().map(str::to_string), 2);
+
+        let mut visits = Vec::new();
+        let total = trie.aggregate_depth_first_root(&mut |value: Option<&mut i32>, position: super::Position<'_, String>, memo: Vec<i32>| {
+            visits.push((joined(position.path), position.segment().cloned(), position.depth()));
+            value.copied().unwrap_or_default() + memo.into_iter().sum::<i32>()
+        });
+        assert_eq!(total, 3);
+        assert_eq!(visits, [
+            ("auth".to_string(), Some("auth".to_string()), 1),
+            ("get person address".to_string(), Some("address".to_string()), 3),
+            ("get person".to_string(), Some("person".to_string()), 2),
+            ("get".to_string(), Some("get".to_string()), 1),
+            ("".to_string(), None, 0),
+        ]);
+    }
+
+    #[test]
+    fn test_walks() {
+        let trie = ["get person address", "get thing", "list"].into_iter()
+            .map(|path| (path.iter_keys().map(str::to_string), path))
+            .collect::<Trie<_, _>>();
+
+        let mut visited = Vec::new();
+        assert!(trie.pre_order(|_, position| {
+            visited.push(joined(position.path));
+            match position.segment().map(String::as_str) {
+                Some("person") => Walk::Prune,
+                _ => Walk::Continue,
+            }
+        }));
+        assert_eq!(visited, ["", "get", "get person", "get thing", "list"]);
+
+        let mut visited = Vec::new();
+        assert!(trie.post_order(|_, position| {
+            visited.push(joined(position.path));
+            Walk::Continue
+        }));
+        assert_eq!(visited, ["get person address", "get person", "get thing", "get", "list", ""]);
+
+        let mut visited = Vec::new();
+        assert!(!trie.pre_order(|value, position| {
+            visited.push(joined(position.path));
+            if value.is_some() { Walk::Stop } else { Walk::Continue }
+        }));
+        assert_eq!(visited, ["", "get", "get person", "get person address"]);
+    }
+
+    #[test]
+    fn test_try_aggregate() {
+        let trie = ["get person", "get thing", "list"].into_iter()
+            .map(|path| (path.iter_keys().map(str::to_string), path.len()))
+            .collect::<Trie<_, _>>();
+
+        let total = trie.aggregate(&mut |value: Option<&usize>, _, memo: Vec<usize>| value.copied().unwrap_or_default() + memo.into_iter().sum::<usize>());
+        assert_eq!(total, 23);
+
+        let mut visited = 0;
+        let result = trie.try_aggregate(&mut |value: Option<&usize>, position: super::Position<'_, String>, _: Vec<()>| {
+            visited += 1;
+            match value {
+                Some(9) => Err(joined(position.path)),
+                _ => Ok(()),
+            }
+        });
+        assert_eq!(result, Err("get thing".to_string()));
+        assert_eq!(visited, 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let trie = [("get person", 1), ("get", 2), ("auth", 3)].into_iter()
+            .map(|(path, value)| (path.iter_keys().map(str::to_string), value))
+            .collect::<Trie<_, _>>();
+
+        let json = serde_json::to_string(&trie).unwrap();
+        assert_eq!(json, r#"{"children":{"auth":{"value":3},"get":{"children":{"person":{"value":1}},"value":2}}}"#);
+
+        let parsed: Trie<String, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.iter().collect::<Vec<_>>(), trie.iter().collect::<Vec<_>>());
+        assert_eq!(serde_json::from_str::<Trie<String, i32>>("{}").unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut trie = Trie::new();
+        trie.insert("get person address".iter_keys(), 1);
+        trie.insert("get thing".iter_keys(), 2);
+
+        assert_eq!(trie.remove(["get", "person"]), None);
+        assert_eq!(trie.remove(["missing", "path"]), None);
+        assert_eq!(trie.remove(["get", "person", "address"]), Some(1));
+        assert!(!trie.contains_prefix(["get", "person"]));
+        assert!(trie.contains_prefix(["get"]));
+
+        assert_eq!(trie.remove(["get", "thing"]), Some(2));
+        assert!(!trie.contains_prefix(["get"]));
+        assert!(trie.is_empty());
+    }
+
+    #[test]
+    fn test_entry() {
+        let mut trie = Trie::new();
+        *trie.entry("get person".iter_keys()).or_insert(0) += 1;
+        *trie.entry("get person".iter_keys()).or_insert(0) += 1;
+        trie.entry("get thing".iter_keys()).and_modify(|count| *count += 1).or_default();
+        trie.entry("get thing".iter_keys()).and_modify(|count| *count += 10).or_default();
+        assert_eq!(trie.lookup(["get", "person"]), Some(&2));
+        assert_eq!(trie.lookup(["get", "thing"]), Some(&10));
+
+        // Vacant entries don't create nodes until a value is inserted
+        assert!(matches!(trie.entry("list people".iter_keys()), Entry::Vacant(_)));
+        assert!(matches!(trie.entry("get".iter_keys()), Entry::Vacant(_)));
+        assert!(!trie.contains_prefix(["list"]));
+        match trie.entry("get person".iter_keys()) {
+            Entry::Occupied(mut entry) => assert_eq!(entry.insert(5), 2),
+            Entry::Vacant(_) => panic!("Expected an occupied entry"),
+        }
+        assert_eq!(trie.lookup(["get", "person"]), Some(&5));
+    }
+
+    #[derive(Clone, Debug)]
+    enum Op {
+        Insert(Vec<u8>, u32),
+        Remove(Vec<u8>),
+        Upsert(Vec<u8>, u32),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        // A small alphabet and short paths, so operations often share prefixes
+        let path = prop::collection::vec(0u8..3, 0..4);
+        prop_oneof![
+            (path.clone(), any::<u32>()).prop_map(|(path, value)| Op::Insert(path, value)),
+            path.clone().prop_map(Op::Remove),
+            (path, any::<u32>()).prop_map(|(path, value)| Op::Upsert(path, value)),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn matches_btree_map_model(ops in prop::collection::vec(op(), 0..64)) {
+            let mut trie = Trie::new();
+            let mut model = BTreeMap::new();
+            for op in ops {
+                match op {
+                    Op::Insert(path, value) => prop_assert_eq!(trie.insert(path.clone(), value), model.insert(path, value)),
+                    Op::Remove(path) => prop_assert_eq!(trie.remove(&path), model.remove(&path)),
+                    Op::Upsert(path, value) => {
+                        trie.entry(path.clone()).and_modify(|old| *old = old.wrapping_add(value)).or_insert(value);
+                        model.entry(path).and_modify(|old: &mut u32| *old = old.wrapping_add(value)).or_insert(value);
+                    }
+                }
+
+                // Lexicographic order of paths is the trie's depth first order
+                let entries = trie.iter().map(|(path, value)| (path.into_iter().copied().collect::<Vec<_>>(), *value)).collect::<Vec<_>>();
+                let expected = model.iter().map(|(path, value)| (path.clone(), *value)).collect::<Vec<_>>();
+                prop_assert_eq!(entries, expected);
+                prop_assert_eq!(trie.len(), model.len());
+
+                for path in model.keys() {
+                    for prefix in 0..=path.len() {
+                        prop_assert!(trie.contains_prefix(&path[..prefix]));
+                    }
+                }
+                // Every remaining node leads to a value, so removed branches were pruned
+                for a in 0..3u8 {
+                    for b in 0..3u8 {
+                        let has_values = model.keys().any(|path| path.starts_with(&[a, b]));
+                        prop_assert_eq!(trie.contains_prefix(&[a, b]), has_values);
+                        prop_assert_eq!(trie.child_keys(&[a, b]).is_some(), has_values);
+                    }
+                }
+            }
+        }
+    }
+}
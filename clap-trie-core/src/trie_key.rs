@@ -0,0 +1,37 @@
+/// A string path of segments, separated by spaces unless another delimiter is given.
+pub trait TrieKey {
+    fn iter_keys(&self) -> impl Iterator<Item = &str>;
+    fn iter_keys_by(&self, delimiter: char) -> impl Iterator<Item = &str>;
+}
+
+impl<T: AsRef<str> + ?Sized> TrieKey for T {
+    fn iter_keys(&self) -> impl Iterator<Item = &str> {
+        self.iter_keys_by(' ')
+    }
+
+    fn iter_keys_by(&self, delimiter: char) -> impl Iterator<Item = &str> {
+        let key = self.as_ref();
+        (!key.is_empty()).then(|| key.split(delimiter)).into_iter().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trie_key::TrieKey;
+
+    #[test]
+    fn test_triekey() {
+        assert_eq!("a b c".iter_keys().collect::<Vec<_>>(), vec!["a", "b", "c"]);
+        assert_eq!("a".iter_keys().next(), Some("a"));
+        assert_eq!("".iter_keys().next(), None);
+        assert_eq!(String::from("a b").iter_keys().collect::<Vec<_>>(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_delimited() {
+        assert_eq!("get:person".iter_keys_by(':').collect::<Vec<_>>(), vec!["get", "person"]);
+        assert_eq!("db.migrate.up".iter_keys_by('.').collect::<Vec<_>>(), vec!["db", "migrate", "up"]);
+        assert_eq!("get person".iter_keys_by(':').collect::<Vec<_>>(), vec!["get person"]);
+        assert_eq!("".iter_keys_by(':').next(), None);
+    }
+}
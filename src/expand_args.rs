@@ -0,0 +1,219 @@
+use convert_case::{Case, Casing};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Expr, ExprLit, Field, FieldsNamed, GenericArgument, Lit, PathArguments, Token, Type, token::Paren};
+
+// How a field is passed on the command line, following clap's derive rules
+#[derive(Default)]
+struct ArgAttrs {
+    long: Option<String>,
+    short: Option<char>,
+    skip: bool,
+    flatten: bool,
+    count: bool,
+    set_false: bool,
+    value_enum: bool,
+}
+
+impl ArgAttrs {
+    fn from_field(field: &Field) -> Self {
+        let name = field.ident.as_ref().unwrap().to_string();
+        let name = name.trim_start_matches("r#");
+        let mut attrs = Self::default();
+        for attr in field.attrs.iter().filter(|attr| is_clap_attr(attr)) {
+            // Ignore anything we don't understand, clap will report errors in it
+            let _ = attr.parse_nested_meta(|meta| {
+                let value = if meta.input.peek(Token![=]) {
+                    Some(meta.value()?.parse::<Expr>()?)
+                } else {
+                    if meta.input.peek(Paren) {
+                        meta.input.parse::<TokenStream>()?;
+                    }
+                    None
+                };
+
+                if meta.path.is_ident("long") {
+                    attrs.long = Some(match value {
+                        Some(Expr::Lit(ExprLit { lit: Lit::Str(lit), .. })) => lit.value(),
+                        _ => name.to_case(Case::Kebab),
+                    });
+                } else if meta.path.is_ident("short") {
+                    attrs.short = match value {
+                        Some(Expr::Lit(ExprLit { lit: Lit::Char(lit), .. })) => Some(lit.value()),
+                        _ => name.chars().next(),
+                    };
+                } else if meta.path.is_ident("value_enum") {
+                    attrs.value_enum = true;
+                } else if meta.path.is_ident("skip") {
+                    attrs.skip = true;
+                } else if meta.path.is_ident("flatten") || meta.path.is_ident("subcommand") {
+                    attrs.flatten = true;
+                } else if meta.path.is_ident("action") {
+                    let action = value.map(|value| quote!(#value).to_string()).unwrap_or_default();
+                    attrs.count = action.ends_with("Count");
+                    attrs.set_false = action.ends_with("SetFalse");
+                }
+                Ok(())
+            });
+        }
+        attrs
+    }
+
+    // Prefer the long flag, it's more readable when logged
+    fn flag(&self) -> Option<String> {
+        match (&self.long, self.short) {
+            (Some(long), _) => Some(format!("--{}", long)),
+            (None, Some(short)) => Some(format!("-{}", short)),
+            (None, None) => None,
+        }
+    }
+
+    // The flag with its value attached, `--long=value` or `-svalue`, so values starting with `-`
+    // aren't parsed as flags
+    fn value_prefix(&self) -> Option<String> {
+        match (&self.long, self.short) {
+            (Some(long), _) => Some(format!("--{}=", long)),
+            (None, Some(short)) => Some(format!("-{}", short)),
+            (None, None) => None,
+        }
+    }
+}
+
+fn is_clap_attr(attr: &Attribute) -> bool {
+    ["arg", "clap", "command"].iter().any(|name| attr.path().is_ident(name))
+}
+
+// Returns the inner type if ty is `wrapper<T>`
+fn unwrap_type<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else { return None };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else { return None };
+    match args.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+fn is_type(ty: &Type, name: &str) -> bool {
+    matches!(ty, Type::Path(path) if path.path.segments.last().is_some_and(|segment| segment.ident == name))
+}
+
+// Whether clap parses the field from its own argument, rather than skipping it or handing it
+// the whole matches
+pub(crate) fn is_value_field(field: &Field) -> bool {
+    let attrs = ArgAttrs::from_field(field);
+    !attrs.skip && !attrs.flatten
+}
+
+pub(crate) fn is_flattened(field: &Field) -> bool {
+    ArgAttrs::from_field(field).flatten
+}
+
+// Converts a reference to a value into an OsString
+pub(crate) fn to_os_string(ty: &Type, value: TokenStream) -> TokenStream {
+    if is_type(ty, "PathBuf") || is_type(ty, "OsString") {
+        quote!(std::ffi::OsString::from(#value.clone()))
+    } else {
+        quote!(std::ffi::OsString::from(#value.to_string()))
+    }
+}
+
+// Generates the body of a `to_args` method for a command struct with the given fields, returning
+// the arguments which would parse back into the struct. Values are rendered with `Display`,
+// except value enums which use their possible value's name, and paths and OS strings which are
+// passed through as they are.
+pub(crate) fn expand_to_args(fields: &FieldsNamed) -> TokenStream {
+    let mut named = Vec::new();
+    let mut positional = Vec::new();
+
+    for field in &fields.named {
+        let ident = field.ident.as_ref().unwrap();
+        let attrs = ArgAttrs::from_field(field);
+        let ty = &field.ty;
+        let flag = attrs.flag();
+
+        if attrs.skip {
+            continue;
+        }
+        if attrs.flatten {
+            named.push(quote!(args.extend(self.#ident.to_args());));
+            continue;
+        }
+
+        let optional = unwrap_type(ty, "Option");
+        let ty = optional.unwrap_or(ty);
+        let multiple = unwrap_type(ty, "Vec");
+        let ty = multiple.unwrap_or(ty);
+
+        // Value enums are rendered as the name clap parses, which needn't match any Display impl
+        let value = match attrs.value_enum {
+            true => quote!(std::ffi::OsString::from(clap::ValueEnum::to_possible_value(value).expect("skipped values can't be converted to arguments").get_name())),
+            false => to_os_string(ty, quote!(value)),
+        };
+        let push = if let Some(flag) = &flag {
+            if attrs.count {
+                quote!(args.extend((0..*value).map(|_| std::ffi::OsString::from(#flag)));)
+            } else if is_type(ty, "bool") && multiple.is_none() {
+                let set = if attrs.set_false { quote!(!*value) } else { quote!(*value) };
+                quote!(if #set { args.push(std::ffi::OsString::from(#flag)); })
+            } else {
+                let prefix = attrs.value_prefix();
+                quote!{
+                    let mut arg = std::ffi::OsString::from(#prefix);
+                    arg.push(#value);
+                    args.push(arg);
+                }
+            }
+        } else {
+            quote!(positionals.push(#value);)
+        };
+
+        let push = match multiple {
+            Some(_) => quote!(for value in value { #push }),
+            None => push,
+        };
+        let push = match optional {
+            Some(_) => quote!(if let Some(value) = &self.#ident { #push }),
+            None => quote!({ let value = &self.#ident; #push }),
+        };
+
+        if flag.is_some() {
+            named.push(push);
+        } else {
+            positional.push(push);
+        }
+    }
+
+    quote!{
+        let mut args: Vec<std::ffi::OsString> = Vec::new();
+        #[allow(unused_mut)]
+        let mut positionals: Vec<std::ffi::OsString> = Vec::new();
+        #(#named)*
+        #(#positional)*
+
+        // Keep positionals which look like flags from being parsed as flags
+        if positionals.iter().any(|arg| arg.to_string_lossy().starts_with('-')) {
+            args.push(std::ffi::OsString::from("--"));
+        }
+        args.extend(positionals);
+        args
+    }
+}
+
+// Generates a function quoting an argument for a POSIX shell, used by generated `Display` impls.
+pub(crate) fn expand_shell_quote() -> TokenStream {
+    quote!{
+        fn shell_quote(arg: &std::ffi::OsStr) -> String {
+            let arg = arg.to_string_lossy();
+            let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:,@+%".contains(c);
+            if !arg.is_empty() && arg.chars().all(is_safe) {
+                arg.into_owned()
+            } else {
+                format!("'{}'", arg.replace('\'', "'\\''"))
+            }
+        }
+    }
+}
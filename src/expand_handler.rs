@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use convert_case::{Case, Casing};
+use proc_macro2::{TokenStream, Ident, Span};
+use quote::{format_ident, quote};
+use syn::{parse::Result, Error};
+
+use crate::expand_trie::TrieItem;
+
+// Doc comment for a handler method, naming the path it handles
+pub(crate) fn handler_doc(item: &TrieItem) -> String {
+    let mut doc = format!("Handles `{}`.", item.path.join(" "));
+    if let Some(about) = &item.about {
+        doc.push_str("\n\n");
+        doc.push_str(about);
+    }
+    doc
+}
+
+// The handler method for a command, its variant name in snake case. Keywords such as `use` or
+// `type` are emitted as raw identifiers, `self`, `super` and `crate` are rejected by
+// `clap_subcommand!` as they can't be.
+pub(crate) fn handler_name(enum_variant_name: &str) -> Ident {
+    let name = enum_variant_name.to_case(Case::Snake);
+    match syn::parse_str::<Ident>(&name) {
+        Ok(_) => Ident::new(&name, Span::call_site()),
+        Err(_) => Ident::new_raw(&name, Span::call_site()),
+    }
+}
+
+// Handler method names come from the path, so differently delimited paths can collide. The error
+// points at the enum declaring the second command in the trie's list.
+pub(crate) fn check_handler_names(items: &[&TrieItem]) -> Result<()> {
+    let mut seen = HashMap::new();
+    for item in items {
+        if let Some(other) = seen.insert(item.handler_name.to_string(), item) {
+            return Err(Error::new(item.enum_name.span(), format!(
+                "Commands `{}` and `{}` would both be handled by `{}`",
+                other.path.join(" "), item.path.join(" "), item.handler_name,
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Generates a handler trait with a method per leaf command, and a `dispatch` method on the trie
+// enum calling the method for the parsed command.
+pub(crate) fn expand_handler(name: &Ident, trait_name: &Ident, items: &[&TrieItem]) -> Result<TokenStream> {
+    check_handler_names(items)?;
+
+    let mut methods = Vec::new();
+    let mut arms = Vec::new();
+    for item in items {
+        let TrieItem { struct_path, enum_path, enum_name, enum_variant_name, handler_name, .. } = item;
+        let doc = handler_doc(item);
+        methods.push(quote!{
+            #[doc = #doc]
+            fn #handler_name(&mut self, cmd: #struct_path) -> Self::Output;
+        });
+        arms.push(quote!{
+            Self::#enum_name(#enum_path::#enum_variant_name(cmd)) => handler.#handler_name(cmd)
+        });
+    }
+
+    let trait_doc = format!("Handles every command of [`{}`], see [`{}::dispatch`].", name, name);
+    Ok(quote!{
+        #[doc = #trait_doc]
+        pub trait #trait_name {
+            /// Value returned by every handler method
+            type Output;
+
+            #(#methods)*
+        }
+
+        impl #name {
+            /// Calls the handler method for this command.
+            pub fn dispatch<H: #trait_name>(self, handler: &mut H) -> H::Output {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+    })
+}
+
+// Generates an async handler trait with an `async fn` per leaf command, taking a shared context,
+// and a `dispatch_async` method on the trie enum awaiting the method for the parsed command.
+pub(crate) fn expand_async_handler(name: &Ident, trait_name: &Ident, items: &[&TrieItem]) -> Result<TokenStream> {
+    check_handler_names(items)?;
+
+    let mut methods = Vec::new();
+    let mut arms = Vec::new();
+    for item in items {
+        let TrieItem { struct_path, enum_path, enum_name, enum_variant_name, handler_name, .. } = item;
+        let doc = handler_doc(item);
+        methods.push(quote!{
+            #[doc = #doc]
+            async fn #handler_name(&self, ctx: &Ctx, cmd: #struct_path) -> Self::Output;
+        });
+        arms.push(quote!{
+            Self::#enum_name(#enum_path::#enum_variant_name(cmd)) => handler.#handler_name(ctx, cmd).await
+        });
+    }
+
+    let trait_doc = format!("Asynchronously handles every command of [`{}`], see [`{}::dispatch_async`].", name, name);
+    Ok(quote!{
+        #[doc = #trait_doc]
+        ///
+        /// `Ctx` is shared state passed to every method, such as a database pool.
+        #[allow(async_fn_in_trait)] // Implementors choose whether their futures are Send
+        pub trait #trait_name<Ctx = ()> {
+            /// Value returned by every handler method
+            type Output;
+
+            #(#methods)*
+        }
+
+        impl #name {
+            /// Calls the async handler method for this command. Doesn't depend on any runtime.
+            pub async fn dispatch_async<Ctx, H: #trait_name<Ctx>>(self, handler: &H, ctx: &Ctx) -> H::Output {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+    })
+}
+
+// Generates a middleware type holding hooks keyed by path prefix, and a `dispatch_with` method on
+// the trie enum running the matching hooks around the handler.
+pub(crate) fn expand_middleware(name: &Ident, trait_name: &Ident) -> TokenStream {
+    let middleware_name = format_ident!("{}Middleware", name);
+    let hook_name = format_ident!("{}Hook", name);
+    let middleware_doc = format!("Hooks run around [`{}::dispatch_with`], keyed by path prefix.", name);
+    quote!{
+        /// Hook run before or after dispatching a command, given the command's path and the context
+        type #hook_name<'a, Ctx, E> = Box<dyn FnMut(&[&str], &mut Ctx) -> Result<(), E> + 'a>;
+
+        #[doc = #middleware_doc]
+        ///
+        /// Before hooks run from the root to the leaf, so hooks on shorter prefixes run first, then
+        /// in the order they were added. After hooks run in the reverse order.
+        pub struct #middleware_name<'a, Ctx, E> {
+            before: Vec<(Vec<String>, #hook_name<'a, Ctx, E>)>,
+            after: Vec<(Vec<String>, #hook_name<'a, Ctx, E>)>,
+        }
+
+        impl<'a, Ctx, E> Default for #middleware_name<'a, Ctx, E> {
+            fn default() -> Self {
+                Self { before: Vec::new(), after: Vec::new() }
+            }
+        }
+
+        impl<'a, Ctx, E> #middleware_name<'a, Ctx, E> {
+            /// Creates middleware without any hooks.
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            /// Adds a hook run before every command under `prefix`, a space separated path. An
+            /// empty prefix matches every command. Returning an error stops the command running.
+            pub fn before(mut self, prefix: &str, hook: impl FnMut(&[&str], &mut Ctx) -> Result<(), E> + 'a) -> Self {
+                self.before.push((prefix.split_whitespace().map(str::to_string).collect(), Box::new(hook)));
+                self.before.sort_by_key(|(prefix, _)| prefix.len());
+                self
+            }
+
+            /// Adds a hook run after every command under `prefix`, a space separated path. An
+            /// empty prefix matches every command.
+            pub fn after(mut self, prefix: &str, hook: impl FnMut(&[&str], &mut Ctx) -> Result<(), E> + 'a) -> Self {
+                self.after.push((prefix.split_whitespace().map(str::to_string).collect(), Box::new(hook)));
+                self.after.sort_by_key(|(prefix, _)| prefix.len());
+                self
+            }
+        }
+
+        impl #name {
+            /// Calls the handler method for this command, running the middleware's matching
+            /// hooks around it.
+            pub fn dispatch_with<Ctx, E, H: #trait_name>(self, middleware: &mut #middleware_name<'_, Ctx, E>, ctx: &mut Ctx, handler: &mut H) -> Result<H::Output, E> {
+                let path = self.info().path;
+                let matches = |prefix: &[String]| prefix.len() <= path.len() && prefix.iter().zip(path).all(|(a, b)| a == b);
+
+                for (_, hook) in middleware.before.iter_mut().filter(|(prefix, _)| matches(prefix)) {
+                    hook(path, ctx)?;
+                }
+                let output = self.dispatch(handler);
+                for (_, hook) in middleware.after.iter_mut().rev().filter(|(prefix, _)| matches(prefix)) {
+                    hook(path, ctx)?;
+                }
+                Ok(output)
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use proc_macro2::{TokenStream, Ident};
+use quote::quote;
+
+use crate::expand_trie::TriePath;
+
+// Generates an interactive REPL for the trie enum: `repl` reading from stdin, `repl_with` reading
+// from any line source, plus the `split_line` and `complete` functions they're built from.
+pub(crate) fn expand_repl(trait_name: &Ident, paths: &[TriePath], abbreviations: bool) -> TokenStream {
+    let paths = paths.iter().map(|TriePath { path, .. }| quote!(&[#(#path),*]));
+    quote!{
+        /// Runs a REPL on stdin, parsing and dispatching each line until end of input. See
+        /// [`Self::repl_with`].
+        pub fn repl<H: #trait_name>(prompt: &str, handler: &mut H) {
+            Self::repl_with(prompt, handler, |prompt| {
+                use std::io::Write;
+
+                print!("{}", prompt);
+                std::io::stdout().flush().ok()?;
+                let mut line = String::new();
+                match std::io::stdin().read_line(&mut line) {
+                    Ok(0) | Err(_) => None,
+                    Ok(_) => Some(line),
+                }
+            })
+        }
+
+        /// Runs a REPL, calling `read_line` with the prompt to get each line until it returns
+        /// `None`. Lines are split like a shell would, then parsed and dispatched to the handler.
+        /// Errors, including help requests, are printed rather than exiting. Values returned by
+        /// the handler are discarded, so handlers should report their own results.
+        pub fn repl_with<H: #trait_name>(prompt: &str, handler: &mut H, mut read_line: impl FnMut(&str) -> Option<String>) {
+            let mut cmd = <Self as clap::Subcommand>::augment_subcommands(clap::Command::new(""))
+                .no_binary_name(true)
+                .subcommand_required(true)
+                .disable_version_flag(true);
+
+            while let Some(line) = read_line(prompt) {
+                let Some(words) = Self::split_line(&line) else {
+                    eprintln!("error: unterminated quote");
+                    continue;
+                };
+                if words.is_empty() {
+                    continue;
+                }
+
+                let command = cmd.try_get_matches_from_mut(words)
+                    .and_then(|matches| <Self as clap::FromArgMatches>::from_arg_matches(&matches));
+                match command {
+                    Ok(command) => { command.dispatch(handler); },
+                    Err(err) => { let _ = err.print(); },
+                }
+            }
+        }
+
+        /// Splits a line into words like a POSIX shell, handling quotes and backslash escapes.
+        /// Returns `None` if a quote isn't closed.
+        pub fn split_line(line: &str) -> Option<Vec<String>> {
+            let mut words = Vec::new();
+            let mut word: Option<String> = None;
+            let mut chars = line.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    c if c.is_whitespace() => words.extend(word.take()),
+                    '\'' => {
+                        let word = word.get_or_insert_with(String::new);
+                        loop {
+                            match chars.next()? {
+                                '\'' => break,
+                                c => word.push(c),
+                            }
+                        }
+                    }
+                    '"' => {
+                        let word = word.get_or_insert_with(String::new);
+                        loop {
+                            match chars.next()? {
+                                '"' => break,
+                                '\\' => match chars.next()? {
+                                    c @ ('"' | '\\' | '$' | '`') => word.push(c),
+                                    '\n' => {}
+                                    c => { word.push('\\'); word.push(c); }
+                                },
+                                c => word.push(c),
+                            }
+                        }
+                    }
+                    '\\' => match chars.next() {
+                        Some('\n') | None => {}
+                        Some(c) => word.get_or_insert_with(String::new).push(c),
+                    },
+                    c => word.get_or_insert_with(String::new).push(c),
+                }
+            }
+            words.extend(word);
+            Some(words)
+        }
+
+        /// Returns the possible completions of the last word of a partially typed line, by
+        /// walking the trie along the words before it.
+        pub fn complete(line: &str) -> Vec<String> {
+            const PATHS: &[&[&str]] = &[#(#paths),*];
+            const ABBREVIATIONS: bool = #abbreviations;
+
+            let mut words = Self::split_line(line)
+                .unwrap_or_else(|| line.split_whitespace().map(str::to_string).collect());
+            let partial = match line.ends_with(char::is_whitespace) {
+                true => String::new(),
+                false => words.pop().unwrap_or_default(),
+            };
+
+            let children = |path: &[&str]| PATHS.iter()
+                .filter(|child| child.len() == path.len() + 1 && child.starts_with(path))
+                .map(|child| child[path.len()])
+                .collect::<Vec<_>>();
+
+            // Resolve the complete words the same way parsing does, any word not naming a child
+            // being a placeholder's value
+            let mut path = Vec::new();
+            for word in &words {
+                let children = children(&path);
+                let placeholder = children.iter().find(|child| child.starts_with('{'));
+                let resolved = match (children.iter().find(|child| **child == word.as_str()), placeholder) {
+                    (Some(child), _) | (None, Some(child)) => *child,
+                    (None, None) if ABBREVIATIONS => match children.iter().filter(|child| child.starts_with(word.as_str())).collect::<Vec<_>>()[..] {
+                        [child] => *child,
+                        _ => return Vec::new(),
+                    },
+                    (None, None) => return Vec::new(),
+                };
+                path.push(resolved);
+            }
+
+            children(&path).into_iter()
+                .filter(|name| !name.starts_with('{') && name.starts_with(partial.as_str()))
+                .map(str::to_string)
+                .collect()
+        }
+    }
+}
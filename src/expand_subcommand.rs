@@ -1,37 +1,68 @@
-use std::{collections::HashMap, sync::RwLock};
+use std::{collections::{HashMap, HashSet}, sync::RwLock};
 
 use convert_case::{Boundary, Case, Casing};
 use quote::{quote, quote_spanned};
-use syn::{LitStr, parse::{Result, Parse, ParseStream}, Token, punctuated::Punctuated, FieldsNamed, Attribute, braced};
+use syn::{meta::ParseNestedMeta, Error, LitChar, LitStr, parse::{Result, Parse, ParseStream}, Token, punctuated::Punctuated, Field, FieldsNamed, Attribute, braced, Expr, ExprLit, Lit, Meta, MetaNameValue, token::Paren};
 use proc_macro2::{TokenStream, Ident, Span};
 
+use crate::{TrieKey, expand_args::{expand_shell_quote, expand_to_args, is_flattened, is_value_field, to_os_string}};
+
 struct SubcommandData {
     name: Ident,
     attrs: Vec<Attribute>,
+    reversed: bool, // Also register two segment keys in reverse, from `#[trie(reversed)]`
+    delimiter: char, // Separates key segments, from `#[trie(delimiter = ':')]`, a space by default
+    to_args: bool, // Generate `to_args` and `Display`, from `#[trie(to_args)]`
     subcommands: Punctuated<Declaration, Token![,]>
 }
 
 impl Parse for SubcommandData {
     fn parse(input: ParseStream) -> Result<Self> {
-        let attrs = input.call(Attribute::parse_outer)?;
+        let mut attrs = input.call(Attribute::parse_outer)?;
+        let mut reversed = false;
+        let mut delimiter = ' ';
+        let mut to_args = false;
+        parse_trie_attrs(&mut attrs, |meta| {
+            if meta.path.is_ident("reversed") {
+                reversed = true;
+                Ok(())
+            } else if meta.path.is_ident("delimiter") {
+                delimiter = meta.value()?.parse::<LitChar>()?.value();
+                Ok(())
+            } else if meta.path.is_ident("to_args") {
+                to_args = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown trie option"))
+            }
+        })?;
         input.parse::<Token![enum]>()?;
         let name: Ident = input.parse()?;
         let content;
         braced!(content in input);
         let subcommands = content.parse_terminated(Declaration::parse, Token![,])?;
 
-        Ok(Self { name, attrs, subcommands })
+        Ok(Self { name, attrs, reversed, delimiter, to_args, subcommands })
     }
 }
 
 impl Parse for Declaration {
     fn parse(input: ParseStream) -> Result<Self> {
-        let attrs = input.call(Attribute::parse_outer)?;
+        let mut attrs = input.call(Attribute::parse_outer)?;
+        let mut alternates = Vec::new();
+        parse_trie_attrs(&mut attrs, |meta| {
+            if meta.path.is_ident("also") {
+                alternates.push(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown trie option"))
+            }
+        })?;
         let name = input.parse::<LitStr>()?;
         input.parse::<Token![=>]>()?;
         let fields = input.parse::<FieldsNamed>()?;
 
-        Ok(Self { name, attrs, fields })
+        Ok(Self { name, attrs, alternates, fields })
     }
 }
 
@@ -39,9 +70,85 @@ impl Parse for Declaration {
 struct Declaration {
     name: LitStr,
     attrs: Vec<Attribute>,
+    alternates: Vec<LitStr>, // Other paths for the same command, from `#[trie(also = "...")]`
     fields: FieldsNamed,
 }
 
+// Removes any `#[trie(...)]` attributes from attrs, so they aren't passed through, calling
+// parse_option for each option in them
+fn parse_trie_attrs(attrs: &mut Vec<Attribute>, mut parse_option: impl FnMut(ParseNestedMeta) -> Result<()>) -> Result<()> {
+    let mut result = Ok(());
+    attrs.retain(|attr| {
+        if !attr.path().is_ident("trie") {
+            return true;
+        }
+        if let Err(err) = attr.parse_nested_meta(&mut parse_option) {
+            result = Err(err);
+        }
+        false
+    });
+    result
+}
+
+// Converts a declared key to the space separated path used by the trie
+fn trie_path(key: &str, delimiter: char) -> std::result::Result<String, String> {
+    let segments = key.iter_keys_by(delimiter).collect::<Vec<_>>();
+    if segments.is_empty() || segments.iter().any(|segment| segment.is_empty() || segment.contains(char::is_whitespace)) {
+        return Err(format!("Invalid trie key `{}`, segments separated by `{}` must be non-empty and can't contain whitespace", key, delimiter));
+    }
+    for (index, segment) in segments.iter().enumerate() {
+        match placeholder(segment) {
+            Some(name) if syn::parse_str::<Ident>(name).is_err() => {
+                return Err(format!("Invalid placeholder `{}` in trie key `{}`, the name between the braces must be a field name", segment, key));
+            }
+            // The value is taken by the command before it, and needs a literal after it to tell
+            // it apart from that command's arguments
+            Some(_) if index == 0 || index == segments.len() - 1 || placeholder(segments[index - 1]).is_some() => {
+                return Err(format!("Placeholder `{}` in trie key `{}` must be between literal segments", segment, key));
+            }
+            Some(_) if segments[..index].contains(segment) => {
+                return Err(format!("Placeholder `{}` appears more than once in trie key `{}`", segment, key));
+            }
+            None if segment.contains(['{', '}']) => {
+                return Err(format!("Invalid trie key `{}`, braces are only allowed around a whole segment, as a placeholder", key));
+            }
+            _ => {}
+        }
+    }
+    Ok(segments.join(" "))
+}
+
+// The name of a `{name}` placeholder segment, which matches any value rather than itself.
+pub(crate) fn placeholder(segment: &str) -> Option<&str> {
+    segment.strip_prefix('{')?.strip_suffix('}')
+}
+
+// Alternates can't take one of the enum's paths, including intermediate commands, nor each
+// other's. clap_trie! checks them against other enums, where only the enum can be pointed at.
+// Sorting reports the same alternate as clap_trie! would.
+fn check_alternates<'a>(keys: impl Iterator<Item = &'a String>, mut alternates: Vec<(String, Span)>) -> Result<()> {
+    let prefixes = |path: &str| {
+        let segments = path.iter_keys().map(str::to_string).collect::<Vec<_>>();
+        (1..=segments.len()).map(move |len| segments[..len].join(" "))
+    };
+    let mut taken = keys.flat_map(|key| prefixes(key)).collect::<HashSet<_>>();
+    alternates.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (alternate, span) in alternates {
+        if taken.contains(&alternate) {
+            return Err(Error::new(span, format!("Alternate path `{}` collides with another command path", alternate)));
+        }
+        taken.extend(prefixes(&alternate));
+    }
+    Ok(())
+}
+
+// The names of the placeholders in a trie path, sorted
+fn placeholder_names(path: &str) -> Vec<&str> {
+    let mut names = path.iter_keys().filter_map(placeholder).collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
 pub(crate) fn expand_subcommand(input: TokenStream) -> Result<TokenStream> {
     let data: SubcommandData = syn::parse2(input)?;
 
@@ -50,26 +157,111 @@ pub(crate) fn expand_subcommand(input: TokenStream) -> Result<TokenStream> {
     let mut cmd_enums = Vec::new();
 
     let mut cmd_names = HashMap::new();
+    let mut to_args_arms = Vec::new();
+    let mut alternate_spans = Vec::new();
 
     for subcommand in data.subcommands {
-        let mut struct_name = subcommand.name.value().with_boundaries(Boundary::all().as_slice()).to_case(Case::Pascal);
+        let key = trie_path(&subcommand.name.value(), data.delimiter).map_err(|err| Error::new(subcommand.name.span(), err))?;
+        let mut struct_name = key.replace(['{', '}'], "").with_boundaries(Boundary::all().as_slice()).to_case(Case::Pascal);
         let enum_variant_name = ident!(&struct_name);
+        // The handler method is named after the command, see `handler_name`
+        let method_name = struct_name.to_case(Case::Snake);
+        if ["self", "super", "crate"].contains(&method_name.as_str()) {
+            return Err(Error::new(subcommand.name.span(), format!("Trie key `{}` can't be a command, `{}` is reserved in Rust", key, method_name)));
+        }
         struct_name.push_str("Cmd");
-        cmd_names.insert(subcommand.name.value(), (enum_variant_name.to_string(), struct_name.clone()));
+        let mut alternates = Vec::new();
+        for alternate in &subcommand.alternates {
+            let path = trie_path(&alternate.value(), data.delimiter).map_err(|err| Error::new(alternate.span(), err))?;
+            if placeholder_names(&path) != placeholder_names(&key) {
+                return Err(Error::new(alternate.span(), format!("Alternate path `{}` must have the same placeholders as `{}`", path, key)));
+            }
+            alternate_spans.push((path.clone(), alternate.span()));
+            alternates.push(path);
+        }
+
+        // Placeholders are stored in the field of the same name, a String unless it's declared
+        let mut fields = subcommand.fields;
+        for name in key.iter_keys().filter_map(placeholder).collect::<Vec<_>>().into_iter().rev() {
+            let ident = ident!(name);
+            if !fields.named.iter().any(|field| field.ident.as_ref() == Some(&ident)) {
+                fields.named.insert(0, syn::parse_quote!(pub #ident: String));
+            }
+        }
+        let placeholder_fields = key.iter_keys().filter_map(placeholder).map(|name| {
+            fields.named.iter().find(|field| field.ident.as_ref().is_some_and(|ident| ident == name)).unwrap().clone()
+        }).collect::<Vec<_>>();
+        // The placeholder's argument is on the command before it, built from the field's type and
+        // doc comment alone, so clap attributes on the field would be ignored
+        for field in &placeholder_fields {
+            if let Some(attr) = field.attrs.iter().find(|attr| is_clap_attr(attr)) {
+                return Err(Error::new_spanned(attr, format!(
+                    "Placeholder field `{}` can't have clap attributes, its value is parsed from `{}` by the command before it",
+                    field.ident.as_ref().unwrap(), key,
+                )));
+            }
+        }
+        if data.reversed {
+            if let [first, second] = key.iter_keys().collect::<Vec<_>>()[..] {
+                let reversed = format!("{} {}", second, first);
+                alternate_spans.push((reversed.clone(), subcommand.name.span()));
+                alternates.push(reversed);
+            }
+        }
+        cmd_names.insert(key.clone(), crate::SubcommandDefinition {
+            enum_variant_name: enum_variant_name.to_string(),
+            struct_name: struct_name.clone(),
+            alternates,
+            about: about(&subcommand.attrs),
+            settings: has_settings(&subcommand.attrs),
+            placeholders: placeholder_fields.iter().map(|field| {
+                let ty = &field.ty;
+                (field.ident.as_ref().unwrap().to_string(), quote!(#ty).to_string(), about(&field.attrs).is_some())
+            }).collect(),
+        });
         let struct_name = ident!(&struct_name);
 
         cmd_enums.push(quote!{
             #enum_variant_name(#struct_name)
         });
 
-        let atts = subcommand.attrs;
-        let fields = subcommand.fields;
-        cmd_structs.push(quote! {
-            #[derive(clap::Parser)]
-            #(#atts)*
-            pub struct #struct_name #fields
+        let path = key.iter_keys().map(|segment| match placeholder_fields.iter().find(|field| placeholder(segment).is_some_and(|name| field.ident.as_ref().unwrap() == name)) {
+            Some(field) => {
+                let ident = &field.ident;
+                to_os_string(&field.ty, quote!(cmd.#ident))
+            }
+            None => quote!(std::ffi::OsString::from(#segment)),
         });
+        to_args_arms.push(quote!{
+            Self::#enum_variant_name(cmd) => [#(#path),*].into_iter().chain(cmd.to_args()).collect()
+        });
+
+        // Placeholders are part of the path, so they're left out of the struct's own arguments
+        let mut arg_fields = fields.clone();
+        arg_fields.named = fields.named.iter().filter(|field| !is_placeholder(&placeholder_fields, field)).cloned().collect();
+        let to_args = expand_to_args(&arg_fields);
+        let atts = subcommand.attrs;
+        let cmd_struct = match placeholder_fields.is_empty() {
+            true => quote! {
+                #[derive(clap::Parser)]
+                #(#atts)*
+                pub struct #struct_name #fields
+            },
+            false => expand_placeholder_struct(&struct_name, atts, fields, &placeholder_fields),
+        };
+        cmd_structs.push(cmd_struct);
+        if data.to_args {
+            cmd_structs.push(quote! {
+                impl #struct_name {
+                    /// Returns the arguments which parse into this command, not including its path.
+                    pub fn to_args(&self) -> Vec<std::ffi::OsString> {
+                        #to_args
+                    }
+                }
+            });
+        }
     }
+    check_alternates(cmd_names.keys(), alternate_spans)?;
 
 
     let enum_name = data.name;
@@ -79,7 +271,8 @@ pub(crate) fn expand_subcommand(input: TokenStream) -> Result<TokenStream> {
     let res = crate::ALL_DECLARATIONS.get_or_init(|| RwLock::new(HashMap::new()))
         .write().expect("Unable to wrap shared declarations in clap-trie proc macro")
         .insert(enum_name.to_string(), crate::SubcommandEnumDefinition {
-            keys: cmd_names
+            keys: cmd_names,
+            to_args: data.to_args,
         });
     if res.is_some() {
         return Ok(quote_spanned!{
@@ -89,12 +282,249 @@ pub(crate) fn expand_subcommand(input: TokenStream) -> Result<TokenStream> {
 
     // Get list of trie keys to commands
     let attrs = data.attrs;
+    let shell_quote = expand_shell_quote();
+    let to_args = data.to_args.then(|| quote! {
+        impl #enum_name {
+            /// Returns the arguments which parse into this command, starting with its path.
+            pub fn to_args(&self) -> Vec<std::ffi::OsString> {
+                match self {
+                    #(#to_args_arms,)*
+                }
+            }
+        }
+
+        impl std::fmt::Display for #enum_name {
+            /// Formats the command's arguments as a shell quoted command line.
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                #shell_quote
+                let args = self.to_args().iter().map(|arg| shell_quote(arg)).collect::<Vec<_>>();
+                f.write_str(&args.join(" "))
+            }
+        }
+    });
     Ok(quote! {
         #(#attrs)*
         pub enum #enum_name {
             #(#cmd_enums,)*
         }
 
+        #to_args
         #(#cmd_structs)*
     })
 }
+
+fn is_clap_attr(attr: &Attribute) -> bool {
+    ["arg", "clap", "command", "group", "value"].iter().any(|name| attr.path().is_ident(name))
+}
+
+fn is_placeholder(placeholder_fields: &[Field], field: &Field) -> bool {
+    placeholder_fields.iter().any(|placeholder| placeholder.ident == field.ident)
+}
+
+// A command struct with placeholders in its path. clap matches them on a parent command, see
+// `Placeholders` in expand_trie, so the struct can't derive its arguments. Instead the other fields
+// are derived on a private struct, and the placeholder values are passed in by the trie enum. Parsed
+// on its own, the struct's command takes the placeholders as its first positional arguments.
+fn expand_placeholder_struct(struct_name: &Ident, attrs: Vec<Attribute>, fields: FieldsNamed, placeholder_fields: &[Field]) -> TokenStream {
+    let is_doc = |attr: &Attribute| attr.path().is_ident("doc");
+
+    // Clap attributes only go on the derived struct, the user's others only on theirs
+    let (clap_attrs, attrs) = attrs.into_iter().partition::<Vec<_>, _>(is_clap_attr);
+    let doc_attrs = attrs.iter().filter(|attr| is_doc(attr));
+    let mut other_fields = fields.clone();
+    other_fields.named = fields.named.iter()
+        .filter(|field| !is_placeholder(placeholder_fields, field))
+        .map(|field| Field { attrs: field.attrs.iter().filter(|attr| is_clap_attr(attr) || is_doc(attr)).cloned().collect(), ..field.clone() })
+        .collect();
+    let mut fields = fields;
+    for field in fields.named.iter_mut() {
+        field.attrs.retain(|attr| !is_clap_attr(attr));
+    }
+
+    let other_idents = other_fields.named.iter().map(|field| &field.ident).collect::<Vec<_>>();
+    // Fields set from a value are updated through a copy of them, flattened ones in place
+    let mut value_fields = other_fields.clone();
+    value_fields.named = other_fields.named.iter().filter(|field| is_value_field(field)).cloned().collect();
+    let value_idents = value_fields.named.iter().map(|field| &field.ident).collect::<Vec<_>>();
+    let flattened_idents = other_fields.named.iter().filter(|field| is_flattened(field)).map(|field| &field.ident);
+    let placeholder_names = placeholder_fields.iter().map(|field| field.ident.as_ref().unwrap().to_string()).collect::<Vec<_>>();
+    let placeholder_updates = placeholder_fields.iter().zip(&placeholder_names).map(|(field, name)| {
+        let (ident, ty) = (&field.ident, &field.ty);
+        quote!{
+            if let Some(value) = matches.try_get_one::<#ty>(#name).ok().flatten() {
+                self.#ident = value.clone();
+            }
+        }
+    });
+    let placeholders = placeholder_fields.iter().map(|field| {
+        let (ident, ty) = (&field.ident, &field.ty);
+        let name = ident.as_ref().unwrap().to_string();
+        let message = format!("the following required arguments were not provided:\n  <{}>\n", name.to_case(Case::UpperSnake));
+        quote!{
+            #ident: placeholders.iter()
+                .find_map(|matches| matches.try_get_one::<#ty>(#name).ok().flatten())
+                .cloned()
+                .ok_or_else(|| clap::Error::raw(clap::error::ErrorKind::MissingRequiredArgument, #message))?
+        }
+    });
+    let placeholder_args = placeholder_fields.iter().map(|field| {
+        let ty = &field.ty;
+        let name = field.ident.as_ref().unwrap().to_string();
+        let value_name = name.to_case(Case::UpperSnake);
+        let help = about(&field.attrs).map(|help| quote!(.help(#help)));
+        quote!(#name => clap::Arg::new(#name).value_name(#value_name).value_parser(clap::value_parser!(#ty)) #help)
+    });
+
+    quote!{
+        #(#attrs)*
+        pub struct #struct_name #fields
+
+        const _: () = {
+            // The command's arguments, other than its placeholders
+            #[derive(clap::Parser)]
+            #(#doc_attrs)*
+            #(#clap_attrs)*
+            struct OtherArgs #other_fields
+
+            // The arguments clap sets from a value, which update copies of the fields
+            #[derive(clap::Args)]
+            #(#clap_attrs)*
+            struct ValueArgs #value_fields
+
+            impl clap::FromArgMatches for #struct_name {
+                fn from_arg_matches(matches: &clap::ArgMatches) -> Result<Self, clap::Error> {
+                    Self::from_placeholder_matches(matches, &[matches])
+                }
+
+                fn update_from_arg_matches(&mut self, matches: &clap::ArgMatches) -> Result<(), clap::Error> {
+                    let mut values = ValueArgs { #(#value_idents: self.#value_idents.clone()),* };
+                    <ValueArgs as clap::FromArgMatches>::update_from_arg_matches(&mut values, matches)?;
+                    let ValueArgs { #(#value_idents),* } = values;
+                    #(self.#value_idents = #value_idents;)*
+                    #(clap::FromArgMatches::update_from_arg_matches(&mut self.#flattened_idents, matches)?;)*
+                    #(#placeholder_updates)*
+                    Ok(())
+                }
+            }
+
+            impl clap::CommandFactory for #struct_name {
+                fn command() -> clap::Command {
+                    let name = <OtherArgs as clap::CommandFactory>::command().get_name().to_string();
+                    let cmd = clap::Command::new(name)
+                        #(.arg(Self::placeholder_arg(#placeholder_names).required(true)))*;
+                    <Self as clap::Args>::augment_args(cmd)
+                }
+
+                fn command_for_update() -> clap::Command {
+                    let name = <OtherArgs as clap::CommandFactory>::command().get_name().to_string();
+                    let cmd = clap::Command::new(name)
+                        #(.arg(Self::placeholder_arg(#placeholder_names)))*;
+                    <Self as clap::Args>::augment_args_for_update(cmd)
+                }
+            }
+
+            impl clap::Parser for #struct_name {}
+
+            impl clap::Args for #struct_name {
+                fn augment_args(cmd: clap::Command) -> clap::Command {
+                    <OtherArgs as clap::Args>::augment_args(cmd)
+                }
+
+                fn augment_args_for_update(cmd: clap::Command) -> clap::Command {
+                    <OtherArgs as clap::Args>::augment_args_for_update(cmd)
+                }
+            }
+
+            impl #struct_name {
+                /// Builds the command from its own matches, taking each placeholder's value from
+                /// the first of `placeholders` which captured it.
+                #[doc(hidden)]
+                pub fn from_placeholder_matches(matches: &clap::ArgMatches, placeholders: &[&clap::ArgMatches]) -> Result<Self, clap::Error> {
+                    let OtherArgs { #(#other_idents),* } = <OtherArgs as clap::FromArgMatches>::from_arg_matches(matches)?;
+                    Ok(Self {
+                        #(#placeholders,)*
+                        #(#other_idents,)*
+                    })
+                }
+
+                /// The positional argument capturing the placeholder `name`, parsed as its field.
+                #[doc(hidden)]
+                pub fn placeholder_arg(name: &str) -> clap::Arg {
+                    match name {
+                        #(#placeholder_args,)*
+                        _ => unreachable!("`{}` isn't a placeholder of this command", name),
+                    }
+                }
+            }
+        };
+    }
+}
+
+// Finds the about text clap will use for a command, either from `#[command(about = "...")]` or the
+// first paragraph of its doc comment. Other forms of `about` can't be read at expansion time.
+fn about(attrs: &[Attribute]) -> Option<String> {
+    let mut about = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("command")) {
+        // Ignore anything we don't understand, clap will report errors in it
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("about") && meta.input.peek(Token![=]) {
+                if let Ok(Expr::Lit(ExprLit { lit: Lit::Str(lit), .. })) = meta.value()?.parse::<Expr>() {
+                    about = Some(lit.value());
+                }
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<Expr>()?;
+            } else if meta.input.peek(Paren) {
+                meta.input.parse::<TokenStream>()?;
+            }
+            Ok(())
+        });
+    }
+    if about.is_some() {
+        return about;
+    }
+
+    let docs = attrs.iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(MetaNameValue { path, value: Expr::Lit(ExprLit { lit: Lit::Str(lit), .. }), .. }) if path.is_ident("doc") => Some(lit.value()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let paragraph = docs.lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let about = match paragraph.strip_suffix('.') {
+        Some(stripped) if !paragraph.ends_with("..") => stripped.to_string(),
+        _ => paragraph,
+    };
+    (!about.is_empty()).then_some(about)
+}
+
+// Whether the attributes set anything on the command which `about` doesn't read, such as aliases,
+// `hide`, or an about clap computes. Lazy parents build such commands up front, so their help and
+// parsing see those settings.
+fn has_settings(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| match &attr.meta {
+        Meta::NameValue(MetaNameValue { path, value, .. }) if path.is_ident("doc") => !matches!(value, Expr::Lit(ExprLit { lit: Lit::Str(_), .. })),
+        _ if attr.path().is_ident("command") || attr.path().is_ident("clap") => {
+            let mut settings = false;
+            let parsed = attr.parse_nested_meta(|meta| {
+                let value = match meta.input.peek(Token![=]) {
+                    true => Some(meta.value()?.parse::<Expr>()?),
+                    false => None,
+                };
+                if meta.input.peek(Paren) {
+                    meta.input.parse::<TokenStream>()?;
+                }
+                let literal_about = meta.path.is_ident("about") && matches!(value, Some(Expr::Lit(ExprLit { lit: Lit::Str(_), .. })));
+                settings |= !literal_about;
+                Ok(())
+            });
+            settings || parsed.is_err()
+        }
+        _ => false,
+    })
+}
@@ -1,18 +1,25 @@
 use proc_macro2::{TokenStream, Ident, Span};
-use quote::{quote, quote_spanned};
-use syn::{parse::{Result, Parse, ParseStream}, punctuated::Punctuated, Token, braced, Path, LitStr, PathSegment, PathArguments, Attribute};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{meta::ParseNestedMeta, parse::{Result, Parse, ParseStream}, punctuated::Punctuated, Token, braced, Path, LitStr, PathSegment, PathArguments, Attribute};
 
-use crate::{Trie, TrieKey};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use clap_trie_core::Walk;
+use convert_case::{Case, Casing};
+
+use crate::{TrieKey, perfect_hash::PerfectHash, expand_args::expand_shell_quote, expand_repl::expand_repl, expand_handler::{expand_async_handler, expand_handler, expand_middleware, handler_name}, expand_subcommand::placeholder};
 
 struct ClapTrieData {
     name: Ident,
     attrs: Vec<Attribute>,
+    options: TrieOptions,
     enum_paths: Punctuated<Path, Token![,]>,
 }
 
 impl Parse for ClapTrieData {
     fn parse(input: ParseStream) -> Result<Self> {
-        let attrs = input.call(Attribute::parse_outer)?;
+        let mut attrs = input.call(Attribute::parse_outer)?;
+        let options = TrieOptions::from_attrs(&mut attrs)?;
         input.parse::<Token![enum]>()?;
         let name: Ident = input.parse()?;
         let content;
@@ -22,16 +29,130 @@ impl Parse for ClapTrieData {
         Ok(Self {
             name,
             attrs,
+            options,
             enum_paths,
         })
     }
 }
 
-struct TrieItem {
-    struct_path: Path,
-    enum_path: Path,
-    enum_name: Ident,
-    enum_variant_name: Ident,
+// Options controlling what gets generated, given as `#[trie(...)]` attributes on the enum.
+#[derive(Default)]
+struct TrieOptions {
+    completions: bool, // Generate `generate_completions`, requires clap_complete
+    man: bool, // Generate `generate_man_pages`, requires clap_mangen
+    markdown: bool, // Generate `generate_markdown`
+    handler: Option<Ident>, // Name of the handler trait to generate, along with `dispatch`
+    async_handler: Option<Ident>, // Name of the async handler trait to generate, along with `dispatch_async`
+    middleware: bool, // Generate a middleware type and `dispatch_with`, implies handler
+    repl: bool, // Generate `repl`, `repl_with` and `complete`, implies handler
+    abbreviations: bool, // Accept unambiguous prefixes of each path segment
+    flat: bool, // Emit each path as a single subcommand, `get person` becomes `get-person`
+    nested: bool, // Keep the nested paths alongside the flat ones, only meaningful with flat
+    lazy: bool, // Defer building each command's args and subcommands until clap reaches it
+}
+
+impl TrieOptions {
+    // Removes any `#[trie(...)]` attributes from attrs, so they aren't passed through to the enum
+    fn from_attrs(attrs: &mut Vec<Attribute>) -> Result<Self> {
+        let mut options = Self::default();
+        let mut result = Ok(());
+        attrs.retain(|attr| {
+            if !attr.path().is_ident("trie") {
+                return true;
+            }
+            if let Err(err) = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("completions") {
+                    options.completions = true;
+                    Ok(())
+                } else if meta.path.is_ident("man") {
+                    options.man = true;
+                    Ok(())
+                } else if meta.path.is_ident("markdown") {
+                    options.markdown = true;
+                    Ok(())
+                } else if meta.path.is_ident("handler") {
+                    options.handler = Some(trait_name(&meta, "Handler")?);
+                    Ok(())
+                } else if meta.path.is_ident("async_handler") {
+                    options.async_handler = Some(trait_name(&meta, "AsyncHandler")?);
+                    Ok(())
+                } else if meta.path.is_ident("middleware") {
+                    options.middleware = true;
+                    Ok(())
+                } else if meta.path.is_ident("repl") {
+                    options.repl = true;
+                    Ok(())
+                } else if meta.path.is_ident("abbreviations") {
+                    options.abbreviations = true;
+                    Ok(())
+                } else if meta.path.is_ident("flat") {
+                    options.flat = true;
+                    Ok(())
+                } else if meta.path.is_ident("nested") {
+                    options.nested = true;
+                    Ok(())
+                } else if meta.path.is_ident("lazy") {
+                    options.lazy = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown trie option"))
+                }
+            }) {
+                result = Err(err);
+            }
+            false
+        });
+        if (options.middleware || options.repl) && options.handler.is_none() {
+            options.handler = Some(ident!("Handler"));
+        }
+        result.map(|_| options)
+    }
+
+    // The keys a path is inserted into the trie under, the canonical one first
+    fn keys(&self, path: &str) -> Vec<String> {
+        let flat = path.iter_keys().collect::<Vec<_>>().join("-");
+        match (self.flat, self.nested) {
+            (false, _) => vec![path.to_string()],
+            (true, true) if flat != path => vec![path.to_string(), flat],
+            (true, _) => vec![flat],
+        }
+    }
+}
+
+// Parses an option which is either bare, using the default name, or given a name as `option = "Name"`
+fn trait_name(meta: &ParseNestedMeta, default: &str) -> Result<Ident> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<LitStr>()?.parse()
+    } else {
+        Ok(ident!(default))
+    }
+}
+
+// The radix trie merges chains of single child commands, which is faster to walk for large command sets
+#[cfg(not(feature = "radix"))]
+type CommandTrie = clap_trie_core::Trie<String, TrieItem>;
+#[cfg(feature = "radix")]
+type CommandTrie = clap_trie_core::RadixTrie<String, TrieItem>;
+type Position<'a> = clap_trie_core::Position<'a, String>;
+
+// A path as written in declarations, its segments separated by spaces
+fn joined(path: &[&String]) -> String {
+    path.iter().map(|segment| segment.as_str()).collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Clone)]
+pub(crate) struct TrieItem {
+    pub(crate) path: Vec<String>,
+    pub(crate) struct_path: Path,
+    pub(crate) enum_path: Path,
+    pub(crate) enum_name: Ident,
+    pub(crate) enum_variant_name: Ident,
+    pub(crate) handler_name: Ident,
+    pub(crate) about: Option<String>,
+    pub(crate) settings: bool, // The command struct sets attributes other than its about, so lazy parents build it up front
+    pub(crate) canonical: bool, // The path commands are listed, handled and converted back to args under
+    pub(crate) hidden: bool, // An alternate path of another command, hidden from help and generated output
+    pub(crate) placeholders: Vec<(String, String, bool)>, // Name of each placeholder in the path, with its field's type and whether it has help text
 }
 
 pub(crate) fn expand_trie(input: TokenStream) -> Result<TokenStream> {
@@ -40,8 +161,11 @@ pub(crate) fn expand_trie(input: TokenStream) -> Result<TokenStream> {
         .get().expect("The clap_trie macro must be called after all clap_subcommand definitions")
         .read().expect("Unable to read lock in clap_trie subcommand");
 
-    let mut trie = Trie::new();
+    let mut trie = CommandTrie::new();
     let mut enum_variants = Vec::new();
+    let mut alternates = Vec::new();
+    let mut placeholder_key = None;
+    let mut has_to_args = true;
 
     for enum_path in data.enum_paths {
         let enum_name = &enum_path.segments.last().unwrap().ident;
@@ -51,40 +175,366 @@ pub(crate) fn expand_trie(input: TokenStream) -> Result<TokenStream> {
             });
         };
 
-        enum_variants.push(quote!(#enum_name(#enum_path)));
+        enum_variants.push((enum_name.clone(), enum_path.clone()));
+        has_to_args &= declarations.to_args;
 
         // Create trie
-        for (key, (enum_variant_name, struct_name)) in &declarations.keys {
-            let struct_name = ident!(struct_name);
+        for (key, definition) in &declarations.keys {
+            let struct_name = ident!(&definition.struct_name);
             let struct_path = change_path_ident!(enum_path, struct_name.clone());
-            let res = trie.insert(key, TrieItem {
+            let item = TrieItem {
+                path: key.iter_keys().map(str::to_string).collect(),
                 struct_path,
                 enum_path: enum_path.clone(),
                 enum_name: enum_name.clone(),
-                enum_variant_name: ident!(enum_variant_name),
-            });
-            if res.is_some() {
-                println!("Duplicate variant: {}", key);
-                return Ok(quote_spanned!{
-                    data.name.span() => compile_error!("All trie command enum variants must be unique")
-                });
+                enum_variant_name: ident!(&definition.enum_variant_name),
+                handler_name: handler_name(&definition.enum_variant_name),
+                about: definition.about.clone(),
+                settings: definition.settings,
+                canonical: true,
+                hidden: false,
+                placeholders: definition.placeholders.clone(),
+            };
+            if !definition.placeholders.is_empty() {
+                placeholder_key = Some(key.clone());
             }
+            for alternate in &definition.alternates {
+                for trie_key in data.options.keys(alternate) {
+                    alternates.push((trie_key, TrieItem {
+                        path: alternate.iter_keys().map(str::to_string).collect(),
+                        canonical: false,
+                        hidden: true,
+                        ..item.clone()
+                    }));
+                }
+            }
+            for (index, trie_key) in data.options.keys(key).into_iter().enumerate() {
+                let res = trie.insert(trie_key.iter_keys().map(str::to_string), TrieItem { canonical: index == 0, ..item.clone() });
+                if res.is_some() {
+                    println!("Duplicate variant: {}", trie_key);
+                    return Ok(quote_spanned!{
+                        data.name.span() => compile_error!("All trie command enum variants must be unique")
+                    });
+                }
+            }
+        }
+    }
+
+    // Alternates are added once every real path is known, so any collision is caught. Sorting
+    // keeps which alternate gets reported the same between builds.
+    alternates.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (alternate, item) in alternates {
+        if trie.child_keys(alternate.iter_keys()).is_some() {
+            let message = format!("Alternate path `{}` collides with another command path", alternate.iter_keys().collect::<Vec<_>>().join(" "));
+            return Ok(quote_spanned!{
+                item.enum_name.span() => compile_error!(#message);
+            });
         }
+        trie.insert(alternate.iter_keys().map(str::to_string), item);
     }
 
+    let placeholders = match Placeholders::new(&trie) {
+        Ok(placeholders) => placeholders,
+        Err(message) => return Ok(quote_spanned!{
+            data.name.span() => compile_error!(#message);
+        }),
+    };
+    let unsupported = [(data.options.flat, "flat"), (data.options.abbreviations, "abbreviations")];
+    if let (Some(key), Some((_, option))) = (&placeholder_key, unsupported.iter().find(|(set, _)| *set)) {
+        let message = format!("The placeholder in `{}` can't be used with `#[trie({})]`", key, option);
+        return Ok(quote_spanned!{
+            data.name.span() => compile_error!(#message);
+        });
+    }
+
+    let abbreviations = match data.options.abbreviations {
+        true => Abbreviations::new(&trie),
+        false => Abbreviations::default(),
+    };
+
     // Visit command trie from bottom up. Creating each step of the recursion on the way
-    let from_arg_aggregate = trie.aggregate_depth_first_root(&mut from_arg_aggregate);
-    let subcommand_aggregate = trie.aggregate_depth_first(&mut subcommand_aggregate);
-    let subcommand_names = trie.child_keys("").unwrap();
+    let from_arg_aggregate = trie.aggregate_depth_first_root(&mut |value: Option<&mut TrieItem>, position: Position<'_>, memo| {
+        from_arg_aggregate(value, position, memo, &abbreviations, &placeholders)
+    });
+    let mut subcommand_aggregate = trie.aggregate_depth_first(&mut |value: Option<&mut TrieItem>, position: Position<'_>, memo| {
+        subcommand_aggregate(value, position, memo, &abbreviations, &placeholders, data.options.lazy)
+    });
+    subcommand_aggregate.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+    subcommand_aggregate.extend(abbreviations.ambiguous_commands("").into_iter().map(|(name, command)| (name, true, command)));
+    let subcommand_aggregate = subcommand_aggregate.into_iter().map(|(_, _, command)| command);
+    let subcommand_names = trie.child_keys("".iter_keys()).unwrap().map(String::as_str)
+        .chain(abbreviations.aliases.iter().filter(|(path, _)| !path.contains(' ')).flat_map(|(_, aliases)| aliases.iter().map(String::as_str)))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+    let completions = data.options.completions.then(|| quote!{
+        /// Writes a completion script for `shell` to `buf`, covering every path in the trie.
+        ///
+        /// `cmd` is the root command the trie is mounted under, for example `Cli::command()`.
+        pub fn generate_completions(shell: clap_complete::Shell, cmd: &mut clap::Command, buf: &mut dyn std::io::Write) {
+            let bin_name = cmd.get_bin_name().unwrap_or_else(|| cmd.get_name()).to_string();
+            clap_complete::generate(shell, cmd, bin_name, buf);
+        }
+    });
+
+    let man = data.options.man.then(|| {
+        // A placeholder has no command of its own, its children are under its parent
+        let paths = visible_paths(&trie).into_iter()
+            .filter(|TriePath { path, .. }| path.last().and_then(|segment| placeholder(segment)).is_none())
+            .map(|TriePath { path, .. }| quote!(&[#(#path),*]));
+        quote!{
+            /// Writes a man page for the root command and every path in the trie to `dir`, returning
+            /// the files written. Pages are named after the full path, for example `prog-get-person.1`.
+            ///
+            /// `cmd` is the root command the trie is mounted under, for example `Cli::command()`.
+            pub fn generate_man_pages(mut cmd: clap::Command, dir: impl AsRef<std::path::Path>) -> std::io::Result<Vec<std::path::PathBuf>> {
+                const PATHS: &[&[&str]] = &[#(#paths),*];
+
+                // Building sets each subcommand's display name to its full path, which names the pages
+                cmd.build();
+                let mut written = Vec::with_capacity(PATHS.len());
+                for path in PATHS {
+                    let mut command = &cmd;
+                    for name in path.iter().filter(|name| !name.starts_with('{')) {
+                        command = command.find_subcommand(name).expect("Trie path is missing from the command");
+                    }
+                    let man = clap_mangen::Man::new(command.clone());
+                    let file = dir.as_ref().join(man.get_filename());
+                    man.render(&mut std::fs::File::create(&file)?)?;
+                    written.push(file);
+                }
+                Ok(written)
+            }
+        }
+    });
+
+    let markdown = data.options.markdown.then(|| {
+        let paths = visible_paths(&trie).into_iter().map(|TriePath { path, is_leaf, .. }| quote!((&[#(#path),*], #is_leaf)));
+        quote!{
+            /// Renders the command tree as Markdown. A nested table of contents following the trie is
+            /// followed by a section per leaf command, with its about text, usage and arguments.
+            ///
+            /// `cmd` is the root command the trie is mounted under, for example `Cli::command()`.
+            pub fn generate_markdown(mut cmd: clap::Command) -> String {
+                use std::fmt::Write;
+
+                // (path, is_leaf) for every node in the trie, in depth first order
+                const PATHS: &[(&[&str], bool)] = &[#(#paths),*];
+
+                // Placeholders are skipped, their children are subcommands of their parent
+                fn find<'a>(cmd: &'a mut clap::Command, path: &[&str]) -> &'a mut clap::Command {
+                    path.iter().filter(|name| !name.starts_with('{')).fold(cmd, |command, name| {
+                        command.find_subcommand_mut(name).expect("Trie path is missing from the command")
+                    })
+                }
+
+                fn arg_name(arg: &clap::Arg) -> String {
+                    let value = arg.get_value_names()
+                        .map(|names| names.iter().map(|name| format!("<{}>", name)).collect::<Vec<_>>().join(" "))
+                        .unwrap_or_else(|| format!("<{}>", arg.get_id().as_str().to_uppercase()));
+                    if arg.is_positional() {
+                        return value;
+                    }
+
+                    let mut flags = arg.get_short().map(|short| format!("-{}", short)).into_iter()
+                        .chain(arg.get_long().map(|long| format!("--{}", long)))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    if arg.get_action().takes_values() {
+                        flags.push(' ');
+                        flags.push_str(&value);
+                    }
+                    flags
+                }
+
+                cmd.build();
+                let bin_name = cmd.get_bin_name().unwrap_or_else(|| cmd.get_name()).to_string();
+                let full_name = |path: &[&str]| std::iter::once(bin_name.as_str()).chain(path.iter().copied()).collect::<Vec<_>>().join(" ");
+
+                let mut out = String::new();
+                writeln!(out, "# {}\n", bin_name).unwrap();
+                for (path, is_leaf) in PATHS.iter().filter(|(path, _)| !path.is_empty()) {
+                    let indent = "  ".repeat(path.len() - 1);
+                    let name = full_name(path);
+                    if *is_leaf {
+                        writeln!(out, "{}- [`{}`](#{})", indent, name, name.replace(' ', "-").replace(['{', '}'], "")).unwrap();
+                    } else {
+                        writeln!(out, "{}- `{}`", indent, name).unwrap();
+                    }
+                }
+
+                for (path, _) in PATHS.iter().filter(|(_, is_leaf)| *is_leaf) {
+                    let command = find(&mut cmd, path);
+                    writeln!(out, "\n## {}\n", full_name(path)).unwrap();
+                    if let Some(about) = command.get_long_about().or_else(|| command.get_about()) {
+                        writeln!(out, "{}\n", about).unwrap();
+                    }
+                    writeln!(out, "```text\n{}\n```", command.render_usage()).unwrap();
+
+                    let args = command.get_arguments()
+                        .filter(|arg| !arg.is_hide_set() && !matches!(arg.get_action(), clap::ArgAction::Help | clap::ArgAction::HelpShort | clap::ArgAction::HelpLong | clap::ArgAction::Version))
+                        .collect::<Vec<_>>();
+                    for (title, positional) in [("Arguments", true), ("Options", false)] {
+                        let args = args.iter().filter(|arg| arg.is_positional() == positional).collect::<Vec<_>>();
+                        if args.is_empty() {
+                            continue;
+                        }
+                        writeln!(out, "\n### {}\n", title).unwrap();
+                        for arg in args {
+                            match arg.get_help() {
+                                Some(help) => writeln!(out, "- `{}`: {}", arg_name(arg), help).unwrap(),
+                                None => writeln!(out, "- `{}`", arg_name(arg)).unwrap(),
+                            }
+                        }
+                    }
+                }
+                out
+            }
+        }
+    });
 
-    let attrs = data.attrs;
     let name = data.name;
+    let info_name = format_ident!("{}Info", name);
+    let info_doc = format!("Describes a single command path of [`{}`].", name);
+    let id_name = format_ident!("{}Id", name);
+    let id_doc = format!("Identifies a command of [`{}`] by its position in [`{}::COMMANDS`].", name, name);
+    let index_doc = format!("Position of the command in [`{}::COMMANDS`].", name);
+
+    // One entry per leaf command, in path order
+    let mut commands = Vec::new();
+    let mut id_arms = Vec::new();
+    let mut to_args_arms = Vec::new();
+    for TrieItem { path, struct_path, enum_path, enum_name, enum_variant_name, about, .. } in leaves(&trie) {
+        let flat = path.join("-");
+        to_args_arms.push(quote!{
+            Self::#enum_name(#enum_path::#enum_variant_name(cmd)) => std::iter::once(std::ffi::OsString::from(#flat)).chain(cmd.to_args()).collect()
+        });
+        let struct_name = struct_path.segments.last().unwrap().ident.to_string();
+        let about = match about {
+            Some(about) => quote!(Some(#about)),
+            None => quote!(None),
+        };
+        let index = commands.len();
+        commands.push(quote!{
+            #info_name {
+                path: &[#(#path),*],
+                subcommand_enum: stringify!(#enum_name),
+                variant: stringify!(#enum_variant_name),
+                payload: #struct_name,
+                about: #about,
+            }
+        });
+        id_arms.push(quote!(Self::#enum_name(#enum_path::#enum_variant_name(..)) => #id_name(#index)));
+    }
+
+    // Every path which reaches a command, including alternate and flat ones, with the index of
+    // the command in COMMANDS
+    let command_variants = leaves(&trie).into_iter().map(|leaf| (&leaf.enum_name, &leaf.enum_variant_name)).collect::<Vec<_>>();
+    let mut command_paths = trie_paths(&trie).into_iter().filter(|path| path.is_leaf).map(|TriePath { path, .. }| {
+        let item = trie.lookup(&path).unwrap();
+        let index = command_variants.iter().position(|&variant| variant == (&item.enum_name, &item.enum_variant_name)).unwrap();
+        (path, index)
+    }).collect::<Vec<_>>();
+
+    // Paths with placeholders match many paths, so they're checked after the hash
+    let (placeholder_paths, literal_paths) = command_paths.iter().partition::<Vec<_>, _>(|(path, _)| path.iter().any(|segment| placeholder(segment).is_some()));
+    let lookup_path = match literal_paths.is_empty() {
+        true => quote!(None),
+        false => {
+            let hash = PerfectHash::new(&literal_paths.iter().map(|(path, _)| path.iter().map(String::as_str).collect()).collect::<Vec<_>>());
+            let displacements = hash.displacements.iter().map(|(d1, d2)| quote!((#d1, #d2)));
+            let slots = hash.slots.iter().map(|&slot| {
+                let (path, index) = &literal_paths[slot];
+                quote!((&[#(#path),*], #index))
+            });
+            let slot = hash.expand_slot();
+            quote!{
+                const DISPLACEMENTS: &[(u32, u32)] = &[#(#displacements),*];
+                // Slot -> (the path hashed there, its command's index)
+                const SLOTS: &[(&[&str], usize)] = &[#(#slots),*];
+                let (slot_path, index) = SLOTS[#slot];
+                (slot_path == path).then_some(#id_name(index))
+            }
+        }
+    };
+    let lookup_path = match placeholder_paths.is_empty() {
+        true => lookup_path,
+        false => {
+            let arms = placeholder_paths.iter().map(|(path, index)| {
+                let (patterns, guard) = placeholders.pattern(path);
+                quote!([#(#patterns),*] #guard => Some(#id_name(#index)))
+            });
+            quote!{
+                let found = { #lookup_path };
+                found.or(match path {
+                    #(#arms,)*
+                    _ => None,
+                })
+            }
+        }
+    };
+
+    // Longest first, so the first matching pattern is the longest prefix
+    command_paths.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    let longest_prefix_arms = command_paths.iter().map(|(path, index)| {
+        let len = path.len();
+        let (patterns, guard) = placeholders.pattern(path);
+        quote!([#(#patterns,)* ..] #guard => Some((#len, &Self::COMMANDS[#index])))
+    });
+    let enum_names = enum_variants.iter().map(|(enum_name, _)| enum_name).collect::<Vec<_>>();
+    let enum_paths = enum_variants.iter().map(|(_, enum_path)| enum_path);
+
+    // Subcommand enums convert back to nested paths, so flat commands need their own names
+    let to_args = match data.options.flat && !data.options.nested {
+        true => quote!(match self { #(#to_args_arms,)* }),
+        false => quote!(match self { #(Self::#enum_names(command) => command.to_args(),)* }),
+    };
+    let shell_quote = expand_shell_quote();
+    // Only available if every subcommand enum has it
+    let to_args = has_to_args.then(|| quote!{
+        impl #name {
+            /// Returns the arguments which parse into this command, starting with its path.
+            pub fn to_args(&self) -> Vec<std::ffi::OsString> {
+                #to_args
+            }
+        }
+
+        impl std::fmt::Display for #name {
+            /// Formats the command's arguments as a shell quoted command line.
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                #shell_quote
+                let args = self.to_args().iter().map(|arg| shell_quote(arg)).collect::<Vec<_>>();
+                f.write_str(&args.join(" "))
+            }
+        }
+    });
+
+    let handler = match &data.options.handler {
+        Some(trait_name) => Some(expand_handler(&name, trait_name, &leaves(&trie))?),
+        None => None,
+    };
+    let middleware = match &data.options.handler {
+        Some(trait_name) if data.options.middleware => Some(expand_middleware(&name, trait_name)),
+        _ => None,
+    };
+    let repl = match &data.options.handler {
+        Some(trait_name) if data.options.repl => Some(expand_repl(trait_name, &visible_paths(&trie), data.options.abbreviations)),
+        _ => None,
+    };
+    let async_handler = match &data.options.async_handler {
+        Some(trait_name) => Some(expand_async_handler(&name, trait_name, &leaves(&trie))?),
+        None => None,
+    };
+
+    let attrs = data.attrs;
     Ok(quote!{
         #(#attrs)*
         pub enum #name {
-            #(#enum_variants),*
+            #(#enum_names(#enum_paths)),*
         }
 
+        #to_args
+
         impl clap::FromArgMatches for #name {
             fn from_arg_matches(matches: &clap::ArgMatches) -> Result<Self, clap::Error> {
                 match Some(("", matches)) {
@@ -117,14 +567,299 @@ pub(crate) fn expand_trie(input: TokenStream) -> Result<TokenStream> {
                 }
             }
         }
+
+        #[doc = #info_doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub struct #info_name {
+            /// Path segments from the root, for example `["get", "person"]`
+            pub path: &'static [&'static str],
+            /// Name of the subcommand enum declaring the command
+            pub subcommand_enum: &'static str,
+            /// Name of the command's variant in the subcommand enum
+            pub variant: &'static str,
+            /// Name of the command's struct
+            pub payload: &'static str,
+            /// The command's about text, if it could be read from its attributes
+            pub about: Option<&'static str>,
+        }
+
+        #[doc = #id_doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct #id_name(usize);
+
+        impl #id_name {
+            #[doc = #index_doc]
+            pub fn index(self) -> usize {
+                self.0
+            }
+
+            /// Returns the description of the command.
+            pub fn info(self) -> &'static #info_name {
+                &#name::COMMANDS[self.0]
+            }
+        }
+
+        #handler
+        #async_handler
+        #middleware
+
+        impl #name {
+            /// Every command in the trie, ordered by path.
+            pub const COMMANDS: &'static [#info_name] = &[#(#commands),*];
+
+            /// Returns the id of this command.
+            pub fn id(&self) -> #id_name {
+                match self {
+                    #(#id_arms,)*
+                }
+            }
+
+            /// Returns the description of this command.
+            pub fn info(&self) -> &'static #info_name {
+                self.id().info()
+            }
+
+            /// Finds the command at exactly `path`, alternate and flat paths included, without
+            /// running clap. The paths are in a perfect hash built while expanding, so this costs
+            /// one hash and one comparison. Paths with placeholders are matched after the hash.
+            pub fn lookup_path(path: &[&str]) -> Option<#id_name> {
+                #lookup_path
+            }
+
+            /// Finds the command whose path makes up the most leading arguments, without running
+            /// clap, returning how many arguments its path spans. Useful for routing before
+            /// parsing, or reporting how far an invalid command line got.
+            pub fn longest_prefix(args: &[&str]) -> Option<(usize, &'static #info_name)> {
+                match args {
+                    #(#longest_prefix_arms,)*
+                    _ => None,
+                }
+            }
+
+            #completions
+            #man
+            #markdown
+            #repl
+        }
     })
 }
 
-fn from_arg_aggregate(value: Option<&mut TrieItem>, key: String, memo: Vec<TokenStream>) -> TokenStream {
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct TriePath {
+    pub(crate) path: Vec<String>,
+    pub(crate) is_leaf: bool,
+    pub(crate) hidden: bool, // Only reachable through alternate paths
+}
+
+// Every path in the trie, sorted so generated output is deterministic. Sorting the paths also
+// orders them depth first, with the root first.
+fn trie_paths(trie: &CommandTrie) -> Vec<TriePath> {
+    let mut paths = trie.aggregate(&mut path_aggregate);
+    paths.sort();
+    paths
+}
+
+// Every path shown in help and generated output
+fn visible_paths(trie: &CommandTrie) -> Vec<TriePath> {
+    trie_paths(trie).into_iter().filter(|path| !path.hidden).collect()
+}
+
+// Every leaf command in the trie, ordered by path, without alternate paths
+fn leaves(trie: &CommandTrie) -> Vec<&TrieItem> {
+    let paths = visible_paths(trie);
+    let mut leaves = paths.into_iter()
+        .filter(|path| path.is_leaf)
+        .map(|TriePath { path, .. }| trie.lookup(&path).unwrap())
+        .filter(|item| item.canonical)
+        .collect::<Vec<_>>();
+    // Flattened names can sort differently to the paths they came from
+    leaves.sort_by(|a, b| a.path.cmp(&b.path));
+    leaves
+}
+
+fn path_aggregate(value: Option<&TrieItem>, position: Position<'_>, memo: Vec<Vec<TriePath>>) -> Vec<TriePath> {
+    let path = position.path.iter().map(|segment| segment.to_string()).collect::<Vec<_>>();
+
+    // Each child's paths start with the child itself
+    let hidden = match &value {
+        Some(value) => value.hidden,
+        None => !path.is_empty() && memo.iter().all(|child| child[0].hidden),
+    };
+    let mut paths = vec![TriePath { path, is_leaf: value.is_some(), hidden }];
+    paths.extend(memo.into_iter().flatten());
+    paths
+}
+
+// Prefixes of path segments accepted in place of the full segment
+#[derive(Default)]
+struct Abbreviations {
+    aliases: HashMap<String, Vec<String>>, // path -> unambiguous prefixes of its last segment
+    ambiguous: HashMap<String, BTreeMap<String, Vec<String>>>, // path -> prefix -> children it could be
+}
+
+impl Abbreviations {
+    // Only visible children can be abbreviated, or be candidates for an ambiguous prefix, the
+    // same ones `complete` offers. Alternate paths are only matched by their full names.
+    fn new(trie: &CommandTrie) -> Self {
+        let visible = visible_paths(trie).into_iter().map(|TriePath { path, .. }| path.join(" ")).collect::<HashSet<_>>();
+        let mut abbreviations = Self::default();
+        trie.pre_order(|_, position| {
+            let path = joined(position.path);
+            let child_path = |child: &str| if path.is_empty() { child.to_string() } else { format!("{} {}", path, child) };
+            let children = trie.child_keys(position.path.iter().copied()).unwrap().map(String::as_str).collect::<Vec<_>>();
+
+            for child in children.iter().filter(|child| visible.contains(&child_path(child))) {
+                for (offset, _) in child.char_indices().skip(1) {
+                    let prefix = &child[..offset];
+                    // Exact names always win, hidden ones included
+                    if children.contains(&prefix) {
+                        continue;
+                    }
+
+                    let candidates = trie.child_keys_with_prefix(position.path.iter().copied(), prefix).unwrap()
+                        .filter(|candidate| visible.contains(&child_path(candidate)))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if candidates.len() == 1 {
+                        abbreviations.aliases.entry(child_path(child)).or_default().push(prefix.to_string());
+                    } else {
+                        abbreviations.ambiguous.entry(path.clone()).or_default().insert(prefix.to_string(), candidates);
+                    }
+                }
+            }
+            Walk::Continue
+        });
+        abbreviations
+    }
+
+    // Hidden commands for the ambiguous prefixes under path, which accept any arguments so
+    // from_arg_matches can report the candidates
+    fn ambiguous_commands(&self, path: &str) -> Vec<(String, TokenStream)> {
+        self.ambiguous.get(path).into_iter().flatten().map(|(prefix, _)| {
+            (prefix.clone(), quote!{
+                clap::Command::new(#prefix)
+                    .hide(true)
+                    .arg(clap::Arg::new("args").num_args(..).trailing_var_arg(true).allow_hyphen_values(true))
+            })
+        }).collect()
+    }
+
+    fn ambiguous_arms(&self, path: &str) -> Vec<TokenStream> {
+        self.ambiguous.get(path).into_iter().flatten().map(|(prefix, candidates)| {
+            let message = format!("subcommand '{}' is ambiguous, it could be: {}\n", prefix, candidates.join(", "));
+            quote!{
+                Some((#prefix, _)) => return Err(clap::Error::raw(clap::error::ErrorKind::InvalidSubcommand, #message)),
+            }
+        }).collect()
+    }
+}
+
+// Placeholder segments, written `{name}` in a key, which match any value. clap only matches
+// subcommands by name, so a placeholder's parent takes the value as an optional positional, and
+// the placeholder's children are subcommands of the parent alongside its literal children.
+// Literal children always win, as clap checks for a subcommand before a positional.
+#[derive(Default)]
+pub(crate) struct Placeholders {
+    parents: HashMap<String, Placeholder>, // path of the parent -> the placeholder under it
+}
+
+struct Placeholder {
+    name: String,
+    arg: TokenStream, // Builds the positional argument capturing the value
+    children: Vec<String>, // Names of the placeholder's children
+    literals: Vec<String>, // Names of the parent's literal children
+}
+
+impl Placeholders {
+    fn new(trie: &CommandTrie) -> std::result::Result<Self, String> {
+        let paths = trie_paths(trie);
+        let children = |path: &[String]| paths.iter()
+            .filter(|child| child.path.len() == path.len() + 1 && child.path.starts_with(path))
+            .map(|child| child.path.last().unwrap().clone())
+            .collect::<Vec<_>>();
+
+        let mut placeholders = Self::default();
+        for TriePath { path, .. } in &paths {
+            let Some((segment, parent)) = path.split_last() else { continue };
+            let Some(name) = placeholder(segment) else { continue };
+            let parent_path = parent.join(" ");
+            let (others, literals) = children(parent).into_iter().partition::<Vec<_>, _>(|child| placeholder(child).is_some());
+            if others.len() > 1 {
+                return Err(format!("`{}` can only be followed by one placeholder, found {}", parent_path, others.join(", ")));
+            }
+            if trie.lookup(parent).is_some() {
+                return Err(format!("`{}` is a command, so it can't be followed by the placeholder {}", parent_path, segment));
+            }
+            let children = children(path);
+            if let Some(child) = children.iter().find(|child| literals.contains(child)) {
+                return Err(format!("`{} {} {}` collides with `{} {}`, as both are subcommands of `{}`", parent_path, segment, child, parent_path, child, parent_path));
+            }
+
+            // The parent parses the value for every command under the placeholder, so they must
+            // store it in the same type
+            let leaves = paths.iter()
+                .filter(|leaf| leaf.is_leaf && leaf.path.starts_with(path))
+                .map(|leaf| trie.lookup(&leaf.path).unwrap())
+                .collect::<Vec<_>>();
+            let field = |item: &TrieItem| item.placeholders.iter().find(|(field, ..)| field == name).unwrap().clone();
+            let field_type = |item: &TrieItem| field(item).1;
+            if let Some(leaf) = leaves.iter().find(|leaf| field_type(leaf) != field_type(leaves[0])) {
+                return Err(format!("Placeholder {} is a `{}` in `{}` but a `{}` in `{}`, it must have the same type in every command under `{} {}`",
+                    segment, field_type(leaves[0]), leaves[0].path.join(" "), field_type(leaf), leaf.path.join(" "), parent_path, segment));
+            }
+
+            // Its help text comes from the first command documenting the field
+            let struct_path = &leaves.iter().find(|leaf| field(leaf).2).unwrap_or(&leaves[0]).struct_path;
+            placeholders.parents.insert(parent_path, Placeholder {
+                name: name.to_string(),
+                arg: quote!(#struct_path::placeholder_arg(#name)),
+                children,
+                literals,
+            });
+        }
+        Ok(placeholders)
+    }
+
+    // A slice pattern matching path, with a guard keeping each placeholder from matching the
+    // names clap would take as a subcommand, including its help subcommand
+    fn pattern(&self, path: &[String]) -> (Vec<TokenStream>, TokenStream) {
+        let mut conditions = Vec::new();
+        let patterns = path.iter().enumerate().map(|(index, segment)| {
+            if placeholder(segment).is_none() {
+                return quote!(#segment);
+            }
+            let Placeholder { children, literals, .. } = &self.parents[&path[..index].join(" ")];
+            let binding = format_ident!("segment_{}", index);
+            let taken = literals.iter().chain(children).map(String::as_str).chain(["help"]);
+            conditions.push(quote!(!matches!(*#binding, #(#taken)|*)));
+            quote!(#binding)
+        }).collect();
+        let guard = match conditions.is_empty() {
+            true => quote!(),
+            false => quote!(if #(#conditions)&&*),
+        };
+        (patterns, guard)
+    }
+}
+
+fn from_arg_aggregate(value: Option<&mut TrieItem>, position: Position<'_>, memo: Vec<TokenStream>, abbreviations: &Abbreviations, placeholders: &Placeholders) -> TokenStream {
+    // A placeholder's children are matched as its parent's subcommands
+    if position.segment().and_then(|segment| placeholder(segment)).is_some() {
+        return quote!(#(#memo)*);
+    }
+
     let return_no_subcommand = if let Some(value) = value {
         let TrieItem { struct_path, enum_path, enum_name, enum_variant_name, .. } = value;
+        let names = position.path.iter().filter_map(|segment| placeholder(segment)).collect::<Vec<_>>();
+        let command = match names.is_empty() {
+            true => quote!(<#struct_path as clap::FromArgMatches>::from_arg_matches(matches)?),
+            false => {
+                let captured = names.iter().map(|name| format_ident!("{}_matches", name));
+                quote!(#struct_path::from_placeholder_matches(matches, &[#(#captured),*])?)
+            }
+        };
         quote! {
-            return Ok(Self::#enum_name(#enum_path::#enum_variant_name(<#struct_path as clap::FromArgMatches>::from_arg_matches(matches)?)));
+            return Ok(Self::#enum_name(#enum_path::#enum_variant_name(#command)));
         }
     } else {
         quote! {
@@ -132,33 +867,105 @@ fn from_arg_aggregate(value: Option<&mut TrieItem>, key: String, memo: Vec<Token
         }
     };
 
-    let key = key.key().unwrap_or_default();
+    let ambiguous = abbreviations.ambiguous_arms(&joined(position.path));
+    // The placeholder's value is required before its children, and not allowed before any other
+    // subcommand. Commands under it read the value from these matches.
+    let placeholder = placeholders.parents.get(&joined(position.path)).map(|Placeholder { name, children, .. }| {
+        let captured = format_ident!("{}_matches", name);
+        let missing = format!("the following required arguments were not provided:\n  <{}>\n", name.to_case(Case::UpperSnake));
+        quote!{
+            let #captured = matches;
+            if let Some(name) = matches.subcommand_name() {
+                match (matches!(name, #(#children)|*), matches.get_raw(#name)) {
+                    (true, None) => return Err(clap::Error::raw(clap::error::ErrorKind::MissingRequiredArgument, #missing)),
+                    (false, Some(mut value)) => {
+                        let message = format!("unexpected argument '{}' found\n", value.next().unwrap_or_default().to_string_lossy());
+                        return Err(clap::Error::raw(clap::error::ErrorKind::UnknownArgument, message));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    });
+    let key = position.segment().map(String::as_str).unwrap_or_default();
     quote!{
         Some((#key, matches)) => {
+            #placeholder
             match matches.subcommand() {
                 None => { #return_no_subcommand }
                 #(#memo)*
+                #(#ambiguous)*
                 Some(_) => {}, // Fall through, end of function returns InvalidSubcommand
             }
         }
     }
 }
 
-fn subcommand_aggregate(value: Option<&mut TrieItem>, key: String, memo: Vec<TokenStream>) -> TokenStream {
-    let name = LitStr::new(key.key().unwrap(), Span::call_site());
+// Aggregates to (subcommand name, hidden, command builder), so siblings can be sorted by name.
+// Alternate paths are hidden, as are intermediate commands with only hidden children. Lazy
+// commands only set what their parent's help and parsing need up front, and defer the rest.
+fn subcommand_aggregate(value: Option<&mut TrieItem>, position: Position<'_>, mut memo: Vec<(String, bool, TokenStream)>, abbreviations: &Abbreviations, placeholders: &Placeholders, lazy: bool) -> (String, bool, TokenStream) {
+    let path = joined(position.path);
+    let key = position.segment().unwrap().to_string();
+
+    // A placeholder's children are its parent's subcommands, so it passes them up as they are.
+    // Sorting puts them after the parent's literal children.
+    if placeholder(&key).is_some() {
+        memo.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+        let hidden = memo.iter().all(|(_, hidden, _)| *hidden);
+        let commands = memo.into_iter().map(|(_, _, command)| command);
+        return (key, hidden, quote!(#(#commands),*));
+    }
+
+    let name = LitStr::new(&key, Span::call_site());
     let mut command = quote!(clap::Command::new(#name));
+    if let Some(aliases) = abbreviations.aliases.get(&path) {
+        command.extend(quote!(.aliases([#(#aliases),*])));
+    }
 
-    // Use CommandFactory::augment_args if a command struct exists
-    if let Some(TrieItem { struct_path, .. }) = value {
-        command = quote!{
-            <#struct_path as clap::Args>::augment_args(#command)
-        };
+    let hidden = match &value {
+        Some(value) => value.hidden,
+        None => memo.iter().all(|(_, hidden, _)| *hidden),
+    };
+    if hidden {
+        command.extend(quote!(.hide(true)));
+    }
+
+    // Use CommandFactory::augment_args if a command struct exists. Deferred commands haven't run
+    // it yet when their parent's help lists them, so they take its about up front, or run it up
+    // front if it sets anything else, such as an alias, which their parent needs to see.
+    let mut deferred = quote!(command);
+    if let Some(TrieItem { struct_path, about, settings, .. }) = value {
+        if lazy && *settings {
+            command = quote!(<#struct_path as clap::Args>::augment_args(#command));
+        } else {
+            deferred = quote!(<#struct_path as clap::Args>::augment_args(#deferred));
+            if let (true, Some(about)) = (lazy, about) {
+                command.extend(quote!(.about(#about)));
+            }
+        }
     }
 
     // If there are children aggregates, add them as subcommands
     if ! memo.is_empty() {
-        command.extend(quote!{
-            .args_conflicts_with_subcommands(true) // Don't allow args on intermediate commands
+        memo.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+        // Intermediate commands have no struct to take an about from, so describe their children
+        if value.is_none() {
+            let children = memo.iter().filter(|(_, hidden, _)| !hidden).map(|(name, _, _)| name.as_str()).collect::<Vec<_>>().join(", ");
+            let about = LitStr::new(&format!("Subcommands: {}", children), Span::call_site());
+            command.extend(quote!(.about(#about)));
+        }
+
+        // Don't allow args on intermediate commands, other than a placeholder's value
+        let args = match placeholders.parents.get(&path) {
+            Some(Placeholder { arg, .. }) => quote!(.arg(#arg)),
+            None => quote!(.args_conflicts_with_subcommands(true)),
+        };
+        memo.extend(abbreviations.ambiguous_commands(&path).into_iter().map(|(name, command)| (name, true, command)));
+        let memo = memo.into_iter().map(|(_, _, command)| command);
+        deferred.extend(quote!{
+            #args
             .arg_required_else_help(true)
             .subcommands([
                 #(#memo),*
@@ -166,5 +973,9 @@ fn subcommand_aggregate(value: Option<&mut TrieItem>, key: String, memo: Vec<Tok
         });
     }
 
-    command
+    let command = match lazy {
+        true => quote!(#command.defer(|command| #deferred)),
+        false => quote!({ let command = #command; #deferred }),
+    };
+    (key, hidden, command)
 }
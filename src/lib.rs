@@ -1,28 +1,40 @@
+//! Generates a correct set of enums/structs for Clap given a trie of commands.
+//! For example:
+//! ```ignore
+//! trie_command::build {
+//!   "list devices" -> devices::list_devices,
+//! }
+//! ```
+
 use std::{collections::HashMap, sync::{OnceLock, RwLock}};
 
 use proc_macro::TokenStream;
 use syn::parse_macro_input;
 
-/// Generates a correct set of enums/structs for Clap given a trie of commands.
-/// For example:
-/// ```ignore
-/// trie_command::build {
-///   "list devices" -> devices::list_devices,
-/// }
-/// ```
-
 #[macro_use] mod macros;
+mod expand_args;
+mod expand_handler;
+mod expand_repl;
 mod expand_subcommand;
 mod expand_trie;
-mod trie;
-mod trie_key;
+mod perfect_hash;
 
-pub(crate) use trie::Trie;
-pub(crate) use trie_key::TrieKey;
+pub(crate) use clap_trie_core::TrieKey;
 
 #[derive(Clone, Debug, Default)]
 struct SubcommandEnumDefinition {
-    keys: HashMap<String, (String, String)>, // trie path -> (enum variant name, command struct name)
+    keys: HashMap<String, SubcommandDefinition>, // trie path -> command
+    to_args: bool, // Its commands have `to_args` methods, from `#[trie(to_args)]`
+}
+
+#[derive(Clone, Debug)]
+struct SubcommandDefinition {
+    enum_variant_name: String,
+    struct_name: String,
+    alternates: Vec<String>, // Other trie paths for the same command, registered as hidden aliases
+    about: Option<String>,
+    settings: bool, // Sets command attributes other than an `about` read into the field above
+    placeholders: Vec<(String, String, bool)>, // Name of each `{name}` placeholder in the path, with its field's type and whether it has help text
 }
 
 static ALL_DECLARATIONS: OnceLock<RwLock<HashMap<String, SubcommandEnumDefinition>>> = OnceLock::new();
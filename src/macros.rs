@@ -6,6 +6,7 @@ macro_rules! ident {
     };
 }
 
+#[allow(unused_macros)]
 macro_rules! append_path {
     ($path: expr, $ident: expr) => {
         {
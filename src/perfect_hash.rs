@@ -0,0 +1,218 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Average keys per bucket. Larger buckets make the table smaller but slower to build.
+const LAMBDA: usize = 5;
+
+// A perfect hash of paths, built with CHD (compress, hash and displace): keys are hashed into
+// buckets, then each bucket is given a displacement which moves all of its keys into free slots.
+// Looking a key up is one hash, one displacement and one comparison, with no probing.
+pub struct PerfectHash {
+    pub(crate) seed: u64,
+    pub(crate) displacements: Vec<(u32, u32)>, // Per bucket
+    pub(crate) slots: Vec<usize>, // Slot -> index of the key hashed there
+}
+
+// The three hashes a key's bucket and slot are derived from
+struct Hashes {
+    bucket: u32,
+    f1: u32,
+    f2: u32,
+}
+
+impl Hashes {
+    fn new(seed: u64, path: &[&str]) -> Self {
+        let hash = hash(seed, path);
+        let (h1, h2) = (mix(hash), mix(hash ^ 0x9e37_79b9_7f4a_7c15));
+        Self { bucket: (h1 >> 32) as u32, f1: h1 as u32, f2: h2 as u32 }
+    }
+
+    fn slot(&self, (d1, d2): (u32, u32), len: usize) -> usize {
+        (d2.wrapping_add(self.f1.wrapping_mul(d1)).wrapping_add(self.f2) % len as u32) as usize
+    }
+}
+
+// FNV-1a over each segment followed by 0xff, which never appears in UTF-8, so segment
+// boundaries affect the hash
+fn hash(seed: u64, path: &[&str]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325 ^ seed;
+    for segment in path {
+        for byte in segment.bytes().chain([0xff]) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+// The splitmix64 finalizer, spreading FNV's weak high bits over the whole word
+fn mix(mut hash: u64) -> u64 {
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+impl PerfectHash {
+    // Builds a perfect hash of distinct paths, trying seeds until every bucket can be displaced.
+    pub fn new(paths: &[Vec<&str>]) -> Self {
+        (0..).find_map(|seed| Self::try_seed(seed, paths)).unwrap()
+    }
+
+    fn try_seed(seed: u64, paths: &[Vec<&str>]) -> Option<Self> {
+        let len = paths.len().max(1);
+        let hashes = paths.iter().map(|path| Hashes::new(seed, path)).collect::<Vec<_>>();
+        let bucket_count = len.div_ceil(LAMBDA);
+        let mut buckets = vec![Vec::new(); bucket_count];
+        for (index, hashes) in hashes.iter().enumerate() {
+            buckets[hashes.bucket as usize % bucket_count].push(index);
+        }
+
+        // The largest buckets are the hardest to place, so go first while most slots are free
+        let mut order = (0..buckets.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&bucket| std::cmp::Reverse(buckets[bucket].len()));
+
+        let mut displacements = vec![(0, 0); buckets.len()];
+        let mut slots = vec![None; len];
+        let mut taken = Vec::new();
+        for bucket in order {
+            let keys = &buckets[bucket];
+            if keys.is_empty() {
+                continue;
+            }
+            let displacement = (0..len as u32).flat_map(|d1| (0..len as u32).map(move |d2| (d1, d2))).find(|&displacement| {
+                taken.clear();
+                for &key in keys {
+                    let slot = hashes[key].slot(displacement, len);
+                    if slots[slot].is_some() || taken.contains(&slot) {
+                        return false;
+                    }
+                    taken.push(slot);
+                }
+                true
+            })?;
+            // The search stops at the displacement which fit, so taken holds its slots
+            displacements[bucket] = displacement;
+            for (&key, &slot) in keys.iter().zip(&taken) {
+                slots[slot] = Some(key);
+            }
+        }
+
+        // Unused slots hold any key, lookups compare the path anyway
+        let slots = slots.into_iter().map(|slot| slot.unwrap_or(0)).collect();
+        Some(Self { seed, displacements, slots })
+    }
+
+    // The index of the key `path` would be, if it's one of the keys. Lookups happen in the
+    // generated code, so this is only used to check it.
+    #[cfg(test)]
+    pub fn get(&self, path: &[&str]) -> usize {
+        let hashes = Hashes::new(self.seed, path);
+        let displacement = self.displacements[hashes.bucket as usize % self.displacements.len()];
+        self.slots[hashes.slot(displacement, self.slots.len())]
+    }
+
+    // Generates an expression finding the slot of `path`, a `&[&str]` in scope, given
+    // `DISPLACEMENTS` and `SLOTS` constants holding this hash's tables. Mirrors `get`.
+    pub fn expand_slot(&self) -> TokenStream {
+        let seed = self.seed;
+        quote!{
+            {
+                let mut hash: u64 = 0xcbf2_9ce4_8422_2325 ^ #seed;
+                for segment in path {
+                    for byte in segment.bytes().chain([0xff]) {
+                        hash ^= byte as u64;
+                        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+                    }
+                }
+                let mix = |mut hash: u64| {
+                    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+                    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+                    hash ^ (hash >> 31)
+                };
+                let (h1, h2) = (mix(hash), mix(hash ^ 0x9e37_79b9_7f4a_7c15));
+                let (bucket, f1, f2) = ((h1 >> 32) as u32, h1 as u32, h2 as u32);
+                let (d1, d2) = DISPLACEMENTS[bucket as usize % DISPLACEMENTS.len()];
+                (d2.wrapping_add(f1.wrapping_mul(d1)).wrapping_add(f2) % SLOTS.len() as u32) as usize
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process::Command};
+
+    use clap_trie_core::Trie;
+    use proptest::prelude::*;
+    use quote::quote;
+
+    use super::PerfectHash;
+    use crate::TrieKey;
+
+    #[test]
+    fn test_perfect_hash() {
+        let paths = ["get person", "get person address", "get", "list groups", "auth", "a b", "ab"].iter()
+            .map(|path| path.iter_keys().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let hash = PerfectHash::new(&paths);
+        for (index, path) in paths.iter().enumerate() {
+            assert_eq!(hash.get(path), index);
+        }
+        assert_eq!(hash.slots.len(), paths.len());
+        assert_eq!(hash.displacements.len(), 2);
+
+        // Segment boundaries are part of the key
+        assert_ne!(super::hash(0, &["a", "b"]), super::hash(0, &["ab"]));
+        assert_eq!(PerfectHash::new(&[]).slots, vec![0]);
+    }
+
+    // Compiles the generated lookup into a program checking it finds the same slots as `get`, for
+    // every command of a CLI and some paths which aren't commands
+    #[test]
+    fn test_expand_slot_matches_get() {
+        let trie = [
+            "auth login", "auth logout", "auth status", "config get", "config set", "config list",
+            "get person", "get person address", "get place", "get thing", "list people", "list places",
+            "list groups all", "repo create", "repo clone", "repo delete", "repo fork", "repo view",
+            "pr create", "pr merge", "pr checkout", "pr review approve", "pr review comment", "version",
+        ].into_iter().map(|path| (path.iter_keys(), ())).collect::<Trie<_, _>>();
+        let keys = trie.iter().map(|(path, _)| path.into_iter().copied().collect::<Vec<_>>()).collect::<Vec<_>>();
+        let hash = PerfectHash::new(&keys);
+
+        let others = [vec![], vec!["auth"], vec!["get", "nothing"], vec!["pr", "review", "approve", "now"]];
+        let checks = keys.iter().chain(&others).map(|path| {
+            let expected = hash.get(path);
+            quote!(assert_eq!(SLOTS[slot(&[#(#path),*])], #expected);)
+        });
+        let displacements = hash.displacements.iter().map(|(d1, d2)| quote!((#d1, #d2)));
+        let slots = &hash.slots;
+        let slot = hash.expand_slot();
+        let program = quote!{
+            const DISPLACEMENTS: &[(u32, u32)] = &[#(#displacements),*];
+            const SLOTS: &[usize] = &[#(#slots),*];
+            fn slot(path: &[&str]) -> usize #slot
+            fn main() { #(#checks)* }
+        };
+
+        let dir = std::env::temp_dir().join(format!("clap-trie-perfect-hash-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("main.rs"), program.to_string()).unwrap();
+        let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+        let compiled = Command::new(rustc).arg("--edition=2021").arg("-o").arg(dir.join("main")).arg(dir.join("main.rs")).status().unwrap();
+        assert!(compiled.success());
+        let ran = Command::new(dir.join("main")).status().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(ran.success());
+    }
+
+    proptest! {
+        #[test]
+        fn finds_every_key(paths in prop::collection::btree_set(prop::collection::vec("[a-z]{0,3}", 0..4), 0..200)) {
+            let paths = paths.iter().map(|path| path.iter().map(String::as_str).collect::<Vec<_>>()).collect::<Vec<_>>();
+            let hash = PerfectHash::new(&paths);
+            for (index, path) in paths.iter().enumerate() {
+                prop_assert_eq!(hash.get(path), index);
+            }
+        }
+    }
+}
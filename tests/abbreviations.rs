@@ -0,0 +1,142 @@
+use clap::{Parser, error::ErrorKind};
+use clap_trie::clap_trie;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        #[derive(Debug)]
+        enum Thingies {
+            #[derive(Debug)] "get thingy" => { pub(crate) id: String },
+            #[derive(Debug)] "get thingy attributes" => { pub(crate) id: String },
+            #[derive(Debug)] "get thing" => {},
+            #[derive(Debug)] "grant access" => {},
+            #[derive(Debug)] "list thingy" => {},
+        }
+    }
+}
+
+clap_trie!{
+    #[derive(Debug)]
+    #[trie(abbreviations, repl)]
+    enum Subcommands {
+        sub::Thingies
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+#[derive(Default)]
+struct App {
+    calls: Vec<&'static str>,
+}
+
+impl Handler for App {
+    type Output = ();
+
+    fn get_thingy(&mut self, _cmd: sub::GetThingyCmd) { self.calls.push("get thingy") }
+    fn get_thingy_attributes(&mut self, _cmd: sub::GetThingyAttributesCmd) { self.calls.push("get thingy attributes") }
+    fn get_thing(&mut self, _cmd: sub::GetThingCmd) { self.calls.push("get thing") }
+    fn grant_access(&mut self, _cmd: sub::GrantAccessCmd) { self.calls.push("grant access") }
+    fn list_thingy(&mut self, _cmd: sub::ListThingyCmd) { self.calls.push("list thingy") }
+}
+
+fn parse(args: &[&str]) -> Result<Subcommands, clap::Error> {
+    Cli::try_parse_from(std::iter::once("test").chain(args.iter().copied())).map(|cli| cli.subcommand)
+}
+
+#[test]
+fn unambiguous_prefixes() {
+    let command = parse(&["ge", "thingy", "attr", "a"]).unwrap();
+    assert!(matches!(command, Subcommands::Thingies(sub::Thingies::GetThingyAttributes(sub::GetThingyAttributesCmd { id })) if id == "a"));
+
+    let command = parse(&["gr", "a"]).unwrap();
+    assert!(matches!(command, Subcommands::Thingies(sub::Thingies::GrantAccess(_))));
+
+    let command = parse(&["l", "t"]).unwrap();
+    assert!(matches!(command, Subcommands::Thingies(sub::Thingies::ListThingy(_))));
+
+    // Full names still work, and exact names win over longer siblings
+    let command = parse(&["get", "thing"]).unwrap();
+    assert!(matches!(command, Subcommands::Thingies(sub::Thingies::GetThing(_))));
+    let command = parse(&["get", "thingy", "b"]).unwrap();
+    assert!(matches!(command, Subcommands::Thingies(sub::Thingies::GetThingy(_))));
+}
+
+#[test]
+fn ambiguous_prefixes() {
+    let err = parse(&["g", "thingy", "a"]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidSubcommand);
+    assert!(err.to_string().contains("subcommand 'g' is ambiguous, it could be: get, grant"));
+
+    let err = parse(&["get", "th", "a"]).unwrap_err();
+    assert!(err.to_string().contains("subcommand 'th' is ambiguous, it could be: thing, thingy"));
+
+    let err = parse(&["get", "x"]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidSubcommand);
+}
+
+#[test]
+fn completion_resolves_prefixes() {
+    assert_eq!(Subcommands::complete("ge thingy "), vec!["attributes"]);
+    assert_eq!(Subcommands::complete("ge thingy a"), vec!["attributes"]);
+    assert_eq!(Subcommands::complete("ge th"), vec!["thing", "thingy"]);
+    assert_eq!(Subcommands::complete("g "), Vec::<String>::new());
+}
+
+#[test]
+fn repl_resolves_prefixes() {
+    let mut app = App::default();
+    let mut lines = ["ge thingy attr a", "g thingy a", "gr a"].into_iter().map(str::to_string);
+    Subcommands::repl_with("> ", &mut app, |_| lines.next());
+    assert_eq!(app.calls, vec!["get thingy attributes", "grant access"]);
+}
+
+mod alternates {
+    use clap_trie::{clap_subcommand, clap_trie};
+
+    clap_subcommand!{
+        #[derive(Debug)]
+        #[trie(reversed)]
+        enum Things {
+            #[derive(Debug)] "get person" => {},
+            #[derive(Debug)] "put thing" => {},
+        }
+    }
+
+    clap_trie!{
+        #[derive(Debug)]
+        #[trie(abbreviations, repl)]
+        enum Subcommands {
+            Things
+        }
+    }
+
+    #[derive(Debug, clap::Parser)]
+    #[command(name="test")]
+    pub(crate) struct Cli {
+        #[clap(subcommand)]
+        pub(crate) subcommand: Subcommands,
+    }
+}
+
+#[test]
+fn alternates_are_not_abbreviated() {
+    let parse = |args: &[&str]| alternates::Cli::try_parse_from(std::iter::once("test").chain(args.iter().copied())).map(|cli| cli.subcommand);
+
+    // The reversed `person get` and `thing put` don't make `p` ambiguous
+    let command = parse(&["p", "thing"]).unwrap();
+    assert!(matches!(command, alternates::Subcommands::Things(alternates::Things::PutThing(_))));
+    assert_eq!(alternates::Subcommands::complete("p "), vec!["thing"]);
+    assert_eq!(alternates::Subcommands::complete("p"), vec!["put"]);
+
+    // Alternates still work by their full names, but not abbreviated
+    let command = parse(&["person", "get"]).unwrap();
+    assert!(matches!(command, alternates::Subcommands::Things(alternates::Things::GetPerson(_))));
+    assert_eq!(parse(&["pe", "get"]).unwrap_err().kind(), ErrorKind::InvalidSubcommand);
+    assert_eq!(parse(&["t", "put"]).unwrap_err().kind(), ErrorKind::InvalidSubcommand);
+}
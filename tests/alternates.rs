@@ -0,0 +1,98 @@
+use clap::{CommandFactory, Parser};
+use clap_trie::clap_trie;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        #[derive(Debug)]
+        #[trie(reversed, to_args)]
+        enum People {
+            #[derive(Debug)] "get person" => { pub(crate) id: String },
+            #[derive(Debug)] "delete person" => { pub(crate) id: String },
+            #[derive(Debug)] "admin reset all" => {},
+        }
+    }
+
+    clap_subcommand!{
+        #[derive(Debug)]
+        #[trie(to_args)]
+        enum Groups {
+            #[derive(Debug)]
+            #[trie(also = "ls groups", also = "groups")]
+            "list groups" => {},
+        }
+    }
+}
+
+clap_trie!{
+    #[derive(Debug)]
+    #[trie(handler, markdown)]
+    enum Subcommands {
+        sub::People,
+        sub::Groups,
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+fn parse(args: &[&str]) -> Subcommands {
+    Cli::try_parse_from(std::iter::once("test").chain(args.iter().copied())).unwrap().subcommand
+}
+
+#[test]
+fn reversed_paths_parse_to_the_same_command() {
+    for args in [["get", "person", "a"], ["person", "get", "a"]] {
+        let command = parse(&args);
+        assert!(matches!(command, Subcommands::People(sub::People::GetPerson(sub::GetPersonCmd { ref id })) if id == "a"));
+        assert_eq!(command.to_string(), "get person a");
+    }
+    assert!(matches!(parse(&["person", "delete", "b"]), Subcommands::People(sub::People::DeletePerson(_))));
+
+    // Only two segment paths are reversed
+    assert!(Cli::try_parse_from(["test", "all", "reset", "admin"]).is_err());
+}
+
+#[test]
+fn also_paths_parse_to_the_same_command() {
+    for args in [&["list", "groups"][..], &["ls", "groups"], &["groups"]] {
+        assert!(matches!(parse(args), Subcommands::Groups(sub::Groups::ListGroups(_))));
+    }
+}
+
+#[test]
+fn alternate_paths_are_hidden() {
+    let cmd = Cli::command();
+    let person = cmd.find_subcommand("person").unwrap();
+    assert!(person.is_hide_set());
+    assert!(person.find_subcommand("get").unwrap().is_hide_set());
+    assert!(cmd.find_subcommand("ls").unwrap().is_hide_set());
+    assert!(cmd.find_subcommand("groups").unwrap().is_hide_set());
+
+    let get = cmd.find_subcommand("get").unwrap();
+    assert!(!get.is_hide_set());
+    assert_eq!(get.get_about().unwrap().to_string(), "Subcommands: person");
+}
+
+#[test]
+fn alternate_paths_are_not_commands() {
+    let paths = Subcommands::COMMANDS.iter().map(|info| info.path.join(" ")).collect::<Vec<_>>();
+    assert_eq!(paths, ["admin reset all", "delete person", "get person", "list groups"]);
+
+    let markdown = Subcommands::generate_markdown(Cli::command());
+    assert!(!markdown.contains("person get"));
+    assert!(!markdown.contains("ls groups"));
+}
+
+#[test]
+fn alternate_paths_find_their_command() {
+    let (len, info) = Subcommands::longest_prefix(&["person", "get", "a"]).unwrap();
+    assert_eq!((len, info.path), (2, &["get", "person"][..]));
+    assert_eq!(Subcommands::longest_prefix(&["groups"]).unwrap().1.path, ["list", "groups"]);
+    assert_eq!(Subcommands::lookup_path(&["person", "get"]), Subcommands::lookup_path(&["get", "person"]));
+    assert_eq!(Subcommands::lookup_path(&["ls", "groups"]).unwrap().info().path, ["list", "groups"]);
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use std::sync::Mutex;
+
+use clap::Parser;
+use clap_trie::clap_trie;
+use common::block_on;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        enum Thingies {
+            "list thingy" => {},
+            "get thingy" => { pub(crate) id: String },
+        }
+    }
+}
+
+clap_trie!{
+    #[trie(async_handler)]
+    enum Subcommands {
+        sub::Thingies,
+    }
+}
+
+#[derive(clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+// Shared context, recording every call
+#[derive(Default)]
+struct Log(Mutex<Vec<String>>);
+
+struct App {
+    prefix: &'static str,
+}
+
+impl AsyncHandler<Log> for App {
+    type Output = Result<(), String>;
+
+    async fn list_thingy(&self, ctx: &Log, _cmd: sub::ListThingyCmd) -> Self::Output {
+        ctx.0.lock().unwrap().push(format!("{} list", self.prefix));
+        Ok(())
+    }
+
+    async fn get_thingy(&self, ctx: &Log, cmd: sub::GetThingyCmd) -> Self::Output {
+        if cmd.id.is_empty() {
+            return Err("missing id".to_string());
+        }
+        ctx.0.lock().unwrap().push(format!("{} get {}", self.prefix, cmd.id));
+        Ok(())
+    }
+}
+
+#[test]
+fn dispatch_async() {
+    let app = App { prefix: "app" };
+    let log = Log::default();
+
+    let cli = Cli::try_parse_from(["test", "get", "thingy", "a"]).unwrap();
+    assert_eq!(block_on(cli.subcommand.dispatch_async(&app, &log)), Ok(()));
+    let cli = Cli::try_parse_from(["test", "list", "thingy"]).unwrap();
+    assert_eq!(block_on(cli.subcommand.dispatch_async(&app, &log)), Ok(()));
+    let cli = Cli::try_parse_from(["test", "get", "thingy", ""]).unwrap();
+    assert_eq!(block_on(cli.subcommand.dispatch_async(&app, &log)), Err("missing id".to_string()));
+
+    assert_eq!(*log.0.lock().unwrap(), vec!["app get a", "app list"]);
+}
@@ -0,0 +1,33 @@
+//! Helpers shared by the integration tests.
+
+// Each test crate uses only some of them
+#![allow(dead_code)]
+
+use std::{future::Future, pin::pin, sync::Arc, task::{Context, Poll, Wake, Waker}};
+
+/// Every command below cmd, with its help, so command trees built different ways can be compared.
+pub fn render(cmd: &mut clap::Command, path: &str, out: &mut Vec<String>) {
+    let aliases = cmd.get_all_aliases().map(str::to_string).collect::<Vec<_>>();
+    out.push(format!("{} {:?} hidden={}\n{}", path, aliases, cmd.is_hide_set(), cmd.render_long_help()));
+    for sub in cmd.get_subcommands_mut() {
+        let path = format!("{} {}", path, sub.get_name());
+        render(sub, &path, out);
+    }
+}
+
+/// Minimal executor, the generated code doesn't depend on a runtime.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct Noop;
+    impl Wake for Noop {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let waker = Waker::from(Arc::new(Noop));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use clap::CommandFactory;
+use clap_complete::Shell;
+use clap_trie::clap_trie;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        enum Thingies {
+            /// List every thingy
+            #[command(visible_alias = "ls")]
+            "list thingy" => {},
+            /// Get a single thingy
+            "get thingy" => { pub(crate) id: String },
+            /// Get the attributes of a thingy
+            "get thingy attributes" => { pub(crate) id: String },
+        }
+    }
+}
+
+clap_trie!{
+    #[trie(completions)]
+    enum Subcommands {
+        sub::Thingies
+    }
+}
+
+#[derive(clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+fn completions(shell: Shell) -> String {
+    let mut buf = Vec::new();
+    Subcommands::generate_completions(shell, &mut Cli::command(), &mut buf);
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn intermediate_about() {
+    let cmd = Cli::command();
+    let get = cmd.find_subcommand("get").unwrap();
+    assert_eq!(get.get_about().map(ToString::to_string).as_deref(), Some("Subcommands: thingy"));
+    assert_eq!(get.find_subcommand("thingy").unwrap().get_about().map(ToString::to_string).as_deref(), Some("Get a single thingy"));
+}
+
+#[test]
+fn bash() {
+    let script = completions(Shell::Bash);
+    assert!(script.contains("test__subcmd__get__subcmd__thingy__subcmd__attributes"));
+    assert!(script.contains("opts=\"-h --help thingy ls help\""));
+}
+
+#[test]
+fn zsh() {
+    let script = completions(Shell::Zsh);
+    assert!(script.contains("'get:Subcommands\\: thingy'"));
+    assert!(script.contains("'attributes:Get the attributes of a thingy'"));
+}
+
+#[test]
+fn fish() {
+    let script = completions(Shell::Fish);
+    assert!(script.contains("-a \"get\" -d 'Subcommands: thingy'"));
+    assert!(script.contains("-a \"attributes\" -d 'Get the attributes of a thingy'"));
+}
+
+#[test]
+fn powershell() {
+    let script = completions(Shell::PowerShell);
+    assert!(script.contains("'test;get;thingy;attributes'"));
+    assert!(script.contains("'Subcommands: thingy'"));
+}
@@ -0,0 +1,54 @@
+use clap::Parser;
+use clap_trie::clap_trie;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        #[derive(Debug)]
+        #[trie(delimiter = ':', to_args)]
+        enum People {
+            #[derive(Debug)] "get:person" => { pub(crate) id: String },
+            #[derive(Debug)] "get:person:address" => { pub(crate) id: String },
+        }
+    }
+
+    clap_subcommand!{
+        #[derive(Debug)]
+        #[trie(delimiter = '.', to_args)]
+        enum Migrations {
+            #[derive(Debug)]
+            #[trie(also = "migrate.up")]
+            "db.migrate.up" => {},
+        }
+    }
+}
+
+clap_trie!{
+    #[derive(Debug)]
+    enum Subcommands {
+        sub::People,
+        sub::Migrations,
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+#[test]
+fn delimited_keys_are_nested() {
+    let cli = Cli::try_parse_from(["test", "get", "person", "address", "a"]).unwrap();
+    assert!(matches!(cli.subcommand, Subcommands::People(sub::People::GetPersonAddress(sub::GetPersonAddressCmd { ref id })) if id == "a"));
+    assert_eq!(cli.subcommand.info().path, ["get", "person", "address"]);
+    assert_eq!(cli.subcommand.to_string(), "get person address a");
+
+    let cli = Cli::try_parse_from(["test", "db", "migrate", "up"]).unwrap();
+    assert!(matches!(cli.subcommand, Subcommands::Migrations(sub::Migrations::DbMigrateUp(_))));
+    let cli = Cli::try_parse_from(["test", "migrate", "up"]).unwrap();
+    assert!(matches!(cli.subcommand, Subcommands::Migrations(sub::Migrations::DbMigrateUp(_))));
+
+    assert!(Cli::try_parse_from(["test", "get:person", "a"]).is_err());
+}
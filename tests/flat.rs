@@ -0,0 +1,92 @@
+use clap::{CommandFactory, Parser};
+use clap_trie::clap_trie;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        #[derive(Debug)]
+        #[trie(to_args)]
+        enum Flat {
+            #[derive(Debug)] "get person" => { pub(crate) id: String },
+            #[derive(Debug)] "get person address" => { pub(crate) id: String },
+            #[derive(Debug)] "auth" => {},
+        }
+    }
+
+    clap_subcommand!{
+        #[derive(Debug)]
+        #[trie(to_args)]
+        enum Both {
+            #[derive(Debug)] "list groups" => {},
+        }
+    }
+}
+
+mod flat {
+    use super::sub;
+    use clap_trie::clap_trie;
+
+    clap_trie!{
+        #[derive(Debug)]
+        #[trie(flat)]
+        enum Subcommands {
+            sub::Flat,
+        }
+    }
+}
+
+clap_trie!{
+    #[derive(Debug)]
+    #[trie(flat, nested)]
+    enum Subcommands {
+        sub::Both,
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name="test")]
+struct FlatCli {
+    #[clap(subcommand)]
+    subcommand: flat::Subcommands,
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+#[test]
+fn flat_commands() {
+    let cli = FlatCli::try_parse_from(["test", "get-person-address", "a"]).unwrap();
+    assert!(matches!(cli.subcommand, flat::Subcommands::Flat(sub::Flat::GetPersonAddress(sub::GetPersonAddressCmd { ref id })) if id == "a"));
+    assert_eq!(cli.subcommand.info().path, ["get", "person", "address"]);
+    assert_eq!(cli.subcommand.to_string(), "get-person-address a");
+    let args = std::iter::once("test".into()).chain(cli.subcommand.to_args());
+    assert!(matches!(FlatCli::try_parse_from(args).unwrap().subcommand, flat::Subcommands::Flat(sub::Flat::GetPersonAddress(_))));
+
+    assert!(FlatCli::try_parse_from(["test", "get", "person", "a"]).is_err());
+
+    let names = FlatCli::command().get_subcommands().map(|cmd| cmd.get_name().to_string()).collect::<Vec<_>>();
+    assert_eq!(names, ["auth", "get-person", "get-person-address"]);
+    let paths = flat::Subcommands::COMMANDS.iter().map(|info| info.path.join(" ")).collect::<Vec<_>>();
+    assert_eq!(paths, ["auth", "get person", "get person address"]);
+    assert_eq!(flat::Subcommands::longest_prefix(&["get-person-address", "a"]).map(|(len, info)| (len, info.path)), Some((1, &["get", "person", "address"][..])));
+    assert_eq!(flat::Subcommands::longest_prefix(&["get", "person"]), None);
+    assert_eq!(flat::Subcommands::lookup_path(&["get-person"]).unwrap().info().path, ["get", "person"]);
+    assert_eq!(flat::Subcommands::lookup_path(&["get", "person"]), None);
+}
+
+#[test]
+fn flat_and_nested_commands() {
+    for args in [&["test", "list-groups"][..], &["test", "list", "groups"]] {
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert!(matches!(cli.subcommand, Subcommands::Both(sub::Both::ListGroups(_))));
+        assert_eq!(cli.subcommand.to_string(), "list groups");
+    }
+
+    let names = Cli::command().get_subcommands().map(|cmd| cmd.get_name().to_string()).collect::<Vec<_>>();
+    assert_eq!(names, ["list", "list-groups"]);
+    assert_eq!(Subcommands::COMMANDS.len(), 1);
+}
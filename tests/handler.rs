@@ -0,0 +1,116 @@
+use clap::Parser;
+use clap_trie::clap_trie;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        enum Thingies {
+            "list thingy" => {},
+            "get thingy" => { pub(crate) id: String },
+            "get thingy attributes" => { pub(crate) id: String },
+        }
+    }
+}
+
+mod sub2 {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        enum Other {
+            "get other" => { pub(crate) id: String },
+        }
+    }
+}
+
+clap_trie!{
+    #[trie(handler)]
+    enum Subcommands {
+        sub::Thingies,
+        sub2::Other,
+    }
+}
+
+#[derive(clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+#[derive(Default)]
+struct Recorder {
+    calls: Vec<String>,
+}
+
+impl Handler for Recorder {
+    type Output = usize;
+
+    fn list_thingy(&mut self, _cmd: sub::ListThingyCmd) -> usize {
+        self.calls.push("list thingy".to_string());
+        self.calls.len()
+    }
+
+    fn get_thingy(&mut self, cmd: sub::GetThingyCmd) -> usize {
+        self.calls.push(format!("get thingy {}", cmd.id));
+        self.calls.len()
+    }
+
+    fn get_thingy_attributes(&mut self, cmd: sub::GetThingyAttributesCmd) -> usize {
+        self.calls.push(format!("get thingy attributes {}", cmd.id));
+        self.calls.len()
+    }
+
+    fn get_other(&mut self, cmd: sub2::GetOtherCmd) -> usize {
+        self.calls.push(format!("get other {}", cmd.id));
+        self.calls.len()
+    }
+}
+
+fn dispatch(handler: &mut Recorder, args: &[&str]) -> usize {
+    Cli::try_parse_from(std::iter::once("test").chain(args.iter().copied())).unwrap().subcommand.dispatch(handler)
+}
+
+#[test]
+fn dispatch_calls_handler() {
+    let mut handler = Recorder::default();
+    assert_eq!(dispatch(&mut handler, &["get", "thingy", "attributes", "a"]), 1);
+    assert_eq!(dispatch(&mut handler, &["get", "thingy", "b"]), 2);
+    assert_eq!(dispatch(&mut handler, &["get", "other", "c"]), 3);
+    assert_eq!(dispatch(&mut handler, &["list", "thingy"]), 4);
+    assert_eq!(handler.calls, vec!["get thingy attributes a", "get thingy b", "get other c", "list thingy"]);
+}
+
+mod named {
+    use clap_trie::clap_trie;
+
+    mod sub {
+        use clap_trie::clap_subcommand;
+        clap_subcommand!{
+            enum NamedThingies {
+                "list thingy" => {},
+            }
+        }
+    }
+
+    clap_trie!{
+        #[trie(handler = "ThingyHandler")]
+        enum Named {
+            sub::NamedThingies,
+        }
+    }
+
+    struct Unit;
+
+    impl ThingyHandler for Unit {
+        type Output = &'static str;
+
+        fn list_thingy(&mut self, _cmd: sub::ListThingyCmd) -> &'static str {
+            "listed"
+        }
+    }
+
+    #[test]
+    fn named_handler() {
+        let command = Named::NamedThingies(sub::NamedThingies::ListThingy(sub::ListThingyCmd {}));
+        assert_eq!(command.dispatch(&mut Unit), "listed");
+    }
+}
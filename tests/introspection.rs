@@ -0,0 +1,106 @@
+use clap::Parser;
+use clap_trie::clap_trie;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        enum Thingies {
+            /// List every thingy.
+            ///
+            /// Archived thingies are hidden.
+            "list thingy" => {},
+            /// Get a single
+            /// thingy
+            "get thingy" => { pub(crate) id: String },
+            #[command(about = "Get the attributes of a thingy")]
+            "get thingy attributes" => { pub(crate) id: String },
+        }
+    }
+}
+
+mod sub2 {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        enum Other {
+            "get other" => {},
+        }
+    }
+}
+
+clap_trie!{
+    enum Subcommands {
+        sub::Thingies,
+        sub2::Other,
+    }
+}
+
+#[derive(clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+#[test]
+fn commands() {
+    let paths = Subcommands::COMMANDS.iter().map(|info| info.path).collect::<Vec<_>>();
+    assert_eq!(paths, vec![
+        &["get", "other"][..],
+        &["get", "thingy"],
+        &["get", "thingy", "attributes"],
+        &["list", "thingy"],
+    ]);
+
+    assert_eq!(Subcommands::COMMANDS[2], SubcommandsInfo {
+        path: &["get", "thingy", "attributes"],
+        subcommand_enum: "Thingies",
+        variant: "GetThingyAttributes",
+        payload: "GetThingyAttributesCmd",
+        about: Some("Get the attributes of a thingy"),
+    });
+    assert_eq!(Subcommands::COMMANDS[0].about, None);
+    assert_eq!(Subcommands::COMMANDS[1].about, Some("Get a single thingy"));
+    assert_eq!(Subcommands::COMMANDS[3].about, Some("List every thingy"));
+}
+
+#[test]
+fn info() {
+    let cli = Cli::try_parse_from(["test", "get", "thingy", "attributes", "ASDF"]).unwrap();
+    assert_eq!(cli.subcommand.info().path, &["get", "thingy", "attributes"]);
+
+    let cli = Cli::try_parse_from(["test", "get", "other"]).unwrap();
+    assert_eq!(cli.subcommand.info().subcommand_enum, "Other");
+}
+
+#[test]
+fn longest_prefix() {
+    let (len, info) = Subcommands::longest_prefix(&["get", "thingy", "attributes", "ASDF"]).unwrap();
+    assert_eq!((len, info.path), (3, &["get", "thingy", "attributes"][..]));
+
+    // A bad invocation still reports the command it got as far as
+    let (len, info) = Subcommands::longest_prefix(&["get", "thingy", "colour"]).unwrap();
+    assert_eq!((len, info.variant), (2, "GetThingy"));
+    assert!(Cli::try_parse_from(["test", "get", "thingy", "colour", "extra"]).is_err());
+
+    assert_eq!(Subcommands::longest_prefix(&["get"]), None);
+    assert_eq!(Subcommands::longest_prefix(&["list", "other"]), None);
+    assert_eq!(Subcommands::longest_prefix(&[]), None);
+}
+
+#[test]
+fn lookup_path() {
+    for (index, info) in Subcommands::COMMANDS.iter().enumerate() {
+        let id = Subcommands::lookup_path(info.path).unwrap();
+        assert_eq!((id.index(), id.info()), (index, info));
+    }
+    assert_eq!(Subcommands::lookup_path(&["get", "thingy", "attributes"]).unwrap().info().variant, "GetThingyAttributes");
+
+    // Only whole paths match
+    assert_eq!(Subcommands::lookup_path(&["get"]), None);
+    assert_eq!(Subcommands::lookup_path(&["get", "thingy", "x"]), None);
+    assert_eq!(Subcommands::lookup_path(&["getthingy"]), None);
+    assert_eq!(Subcommands::lookup_path(&[]), None);
+
+    let cli = Cli::try_parse_from(["test", "get", "other"]).unwrap();
+    assert_eq!(Some(cli.subcommand.id()), Subcommands::lookup_path(&["get", "other"]));
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use clap::Parser;
+use clap_trie::clap_trie;
+use common::block_on;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        enum Keywords {
+            "use" => { pub(crate) name: String },
+            "type" => {},
+            "match" => {},
+        }
+    }
+}
+
+clap_trie!{
+    #[trie(handler, async_handler)]
+    enum Subcommands {
+        sub::Keywords,
+    }
+}
+
+#[derive(clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+struct App;
+
+impl Handler for App {
+    type Output = String;
+
+    fn r#use(&mut self, cmd: sub::UseCmd) -> Self::Output {
+        format!("use {}", cmd.name)
+    }
+
+    fn r#type(&mut self, _cmd: sub::TypeCmd) -> Self::Output {
+        "type".to_string()
+    }
+
+    fn r#match(&mut self, _cmd: sub::MatchCmd) -> Self::Output {
+        "match".to_string()
+    }
+}
+
+impl AsyncHandler<()> for App {
+    type Output = String;
+
+    async fn r#use(&self, _ctx: &(), cmd: sub::UseCmd) -> Self::Output {
+        format!("async use {}", cmd.name)
+    }
+
+    async fn r#type(&self, _ctx: &(), _cmd: sub::TypeCmd) -> Self::Output {
+        "async type".to_string()
+    }
+
+    async fn r#match(&self, _ctx: &(), _cmd: sub::MatchCmd) -> Self::Output {
+        "async match".to_string()
+    }
+}
+
+#[test]
+fn keyword_commands() {
+    let parse = |args: &[&str]| Cli::try_parse_from(std::iter::once("test").chain(args.iter().copied())).unwrap().subcommand;
+
+    assert_eq!(parse(&["use", "a"]).dispatch(&mut App), "use a");
+    assert_eq!(parse(&["type"]).dispatch(&mut App), "type");
+    assert_eq!(parse(&["match"]).dispatch(&mut App), "match");
+
+    assert_eq!(block_on(parse(&["use", "a"]).dispatch_async(&App, &())), "async use a");
+    assert_eq!(block_on(parse(&["match"]).dispatch_async(&App, &())), "async match");
+}
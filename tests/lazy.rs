@@ -0,0 +1,123 @@
+mod common;
+
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_trie::clap_trie;
+use common::render;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        #[derive(Debug)]
+        enum Thingies {
+            /// Get a single thingy
+            #[derive(Debug)] "get thingy" => { pub(crate) id: String },
+            /// Get the attributes of a thingy.
+            ///
+            /// Attributes are listed one per line.
+            #[derive(Debug)] "get thingy attributes" => { pub(crate) id: String },
+            #[derive(Debug)]
+            #[command(about = concat!("Get a ", "thing"))]
+            "get thing" => {},
+            #[derive(Debug)]
+            #[trie(also = "ls thingies")]
+            "list thingy" => { #[arg(long)] pub(crate) all: bool },
+            #[derive(Debug)]
+            #[command(visible_alias = "ls")]
+            "list groups" => {},
+        }
+    }
+}
+
+clap_trie!{
+    #[derive(Debug)]
+    #[trie(markdown)]
+    enum Eager {
+        sub::Thingies
+    }
+}
+
+clap_trie!{
+    #[derive(Debug)]
+    #[trie(lazy, markdown)]
+    enum Lazy {
+        sub::Thingies
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name="test")]
+struct EagerCli {
+    #[clap(subcommand)]
+    subcommand: Eager,
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name="test")]
+struct LazyCli {
+    #[clap(subcommand)]
+    subcommand: Lazy,
+}
+
+fn child<'a>(cmd: &'a clap::Command, path: &[&str]) -> &'a clap::Command {
+    path.iter().fold(cmd, |cmd, name| cmd.find_subcommand(name).unwrap())
+}
+
+#[test]
+fn commands_match_eager() {
+    let (mut eager, mut lazy) = (Vec::new(), Vec::new());
+    render(&mut EagerCli::command(), "test", &mut eager);
+    render(&mut LazyCli::command(), "test", &mut lazy);
+    assert_eq!(lazy, eager);
+
+    assert_eq!(Lazy::generate_markdown(LazyCli::command()), Eager::generate_markdown(EagerCli::command()));
+}
+
+#[test]
+fn only_builds_the_parsed_path() {
+    let mut cmd = Lazy::augment_subcommands(clap::Command::new("test"));
+    // Nothing below the top level exists until clap reaches it
+    assert_eq!(child(&cmd, &["get"]).get_subcommands().count(), 0);
+    assert_eq!(child(&cmd, &["get"]).get_about().map(|about| about.to_string()).as_deref(), Some("Subcommands: thing, thingy"));
+
+    cmd.try_get_matches_from_mut(["test", "get", "thingy", "x"]).unwrap();
+    let names = child(&cmd, &["get"]).get_subcommands().map(|cmd| cmd.get_name()).collect::<Vec<_>>();
+    assert_eq!(names, ["thing", "thingy", "help"]);
+    assert_eq!(child(&cmd, &["get", "thingy"]).get_arguments().filter(|arg| arg.get_id() == "id").count(), 1);
+    // Commands next to the path are listed, with their about, but not built
+    let attributes = child(&cmd, &["get", "thingy", "attributes"]);
+    assert_eq!(attributes.get_about().map(|about| about.to_string()).as_deref(), Some("Get the attributes of a thingy"));
+    assert_eq!(attributes.get_arguments().count(), 0);
+    assert_eq!(child(&cmd, &["list"]).get_subcommands().count(), 0);
+}
+
+#[test]
+fn parse() {
+    let cli = LazyCli::try_parse_from(["test", "get", "thingy", "attributes", "x"]).unwrap();
+    assert!(matches!(cli.subcommand, Lazy::Thingies(sub::Thingies::GetThingyAttributes(sub::GetThingyAttributesCmd { id })) if id == "x"));
+    let cli = LazyCli::try_parse_from(["test", "ls", "thingies", "--all"]).unwrap();
+    assert!(matches!(cli.subcommand, Lazy::Thingies(sub::Thingies::ListThingy(sub::ListThingyCmd { all: true }))));
+
+    let err = LazyCli::try_parse_from(["test", "get", "thingg"]).unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::InvalidSubcommand);
+    assert!(err.to_string().contains("thingy"));
+
+    let err = LazyCli::try_parse_from(["test", "help", "get", "thingy", "attributes"]).unwrap_err();
+    assert_eq!(err.kind(), clap::error::ErrorKind::DisplayHelp);
+    assert!(err.to_string().contains("Attributes are listed one per line"));
+}
+
+#[test]
+fn command_settings_match_eager() {
+    // Aliases are set before the parent is parsed
+    assert!(matches!(EagerCli::try_parse_from(["test", "list", "ls"]).unwrap().subcommand, Eager::Thingies(sub::Thingies::ListGroups(_))));
+    assert!(matches!(LazyCli::try_parse_from(["test", "list", "ls"]).unwrap().subcommand, Lazy::Thingies(sub::Thingies::ListGroups(_))));
+
+    // Parsing `get` builds it but not its children, which list their computed abouts all the same
+    let help = |mut cmd: clap::Command| {
+        cmd.try_get_matches_from_mut(["test", "get"]).unwrap_err();
+        cmd.find_subcommand_mut("get").unwrap().render_help().to_string()
+    };
+    let lazy = help(Lazy::augment_subcommands(clap::Command::new("test")));
+    assert_eq!(lazy, help(Eager::augment_subcommands(clap::Command::new("test"))));
+    assert!(lazy.contains("Get a thing"));
+}
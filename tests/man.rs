@@ -0,0 +1,59 @@
+use clap::CommandFactory;
+use clap_trie::clap_trie;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        enum Thingies {
+            /// List every thingy
+            "list thingy" => {},
+            /// Get a single thingy
+            "get thingy" => { pub(crate) id: String },
+            /// Get the attributes of a thingy
+            "get thingy attributes" => { pub(crate) id: String },
+        }
+    }
+}
+
+clap_trie!{
+    #[trie(man)]
+    enum Subcommands {
+        sub::Thingies
+    }
+}
+
+#[derive(clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+#[test]
+fn man_pages() {
+    let dir = std::env::temp_dir().join(format!("clap-trie-man-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let written = Subcommands::generate_man_pages(Cli::command(), &dir).unwrap();
+    let mut names = written.iter().map(|file| file.file_name().unwrap().to_str().unwrap()).collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec![
+        "test-get-thingy-attributes.1",
+        "test-get-thingy.1",
+        "test-get.1",
+        "test-list-thingy.1",
+        "test-list.1",
+        "test.1",
+    ]);
+
+    // Intermediate pages list their children
+    let get = std::fs::read_to_string(dir.join("test-get.1")).unwrap();
+    assert!(get.contains("test\\-get\\-thingy(1)"));
+    assert!(get.contains("Get a single thingy"));
+
+    let leaf = std::fs::read_to_string(dir.join("test-get-thingy-attributes.1")).unwrap();
+    assert!(leaf.contains("Get the attributes of a thingy"));
+    assert!(leaf.contains("\\fIID\\fR"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,99 @@
+use clap::CommandFactory;
+use clap_trie::clap_trie;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        enum Thingies {
+            /// List every thingy
+            "list thingy" => {
+                /// Include archived thingies
+                #[arg(short, long)]
+                all: bool,
+            },
+            /// Get a single thingy
+            "get thingy" => {
+                /// Thingy to get
+                pub(crate) id: String,
+            },
+            /// Get the attributes of a thingy
+            "get thingy attributes" => {
+                pub(crate) id: String,
+                /// Only show this attribute
+                #[arg(long, value_name = "NAME")]
+                pub(crate) only: Option<String>,
+            },
+        }
+    }
+}
+
+clap_trie!{
+    #[trie(markdown)]
+    enum Subcommands {
+        sub::Thingies
+    }
+}
+
+#[derive(clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+#[test]
+fn markdown() {
+    let markdown = Subcommands::generate_markdown(Cli::command());
+    assert_eq!(markdown, r#"# test
+
+- `test get`
+  - [`test get thingy`](#test-get-thingy)
+    - [`test get thingy attributes`](#test-get-thingy-attributes)
+- `test list`
+  - [`test list thingy`](#test-list-thingy)
+
+## test get thingy
+
+Get a single thingy
+
+```text
+Usage: test get thingy <ID>
+       test get thingy <COMMAND>
+```
+
+### Arguments
+
+- `<ID>`: Thingy to get
+
+## test get thingy attributes
+
+Get the attributes of a thingy
+
+```text
+Usage: test get thingy attributes [OPTIONS] <ID>
+```
+
+### Arguments
+
+- `<ID>`
+
+### Options
+
+- `--only <NAME>`: Only show this attribute
+
+## test list thingy
+
+List every thingy
+
+```text
+Usage: test list thingy [OPTIONS]
+```
+
+### Options
+
+- `-a, --all`: Include archived thingies
+"#);
+
+    // Output order doesn't depend on hashing
+    assert_eq!(markdown, Subcommands::generate_markdown(Cli::command()));
+}
@@ -0,0 +1,102 @@
+use clap::Parser;
+use clap_trie::clap_trie;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        #[allow(clippy::enum_variant_names)]
+        enum Admin {
+            "admin user add" => { pub(crate) name: String },
+            "admin user list" => {},
+            "db migrate" => {},
+        }
+    }
+}
+
+clap_trie!{
+    #[trie(middleware)]
+    enum Subcommands {
+        sub::Admin,
+    }
+}
+
+#[derive(clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+#[derive(Default)]
+struct Context {
+    authenticated: bool,
+    events: Vec<String>,
+}
+
+struct App;
+
+impl Handler for App {
+    type Output = &'static str;
+
+    fn admin_user_add(&mut self, _cmd: sub::AdminUserAddCmd) -> &'static str { "add" }
+    fn admin_user_list(&mut self, _cmd: sub::AdminUserListCmd) -> &'static str { "list" }
+    fn db_migrate(&mut self, _cmd: sub::DbMigrateCmd) -> &'static str { "migrate" }
+}
+
+fn parse(args: &[&str]) -> Subcommands {
+    Cli::try_parse_from(std::iter::once("test").chain(args.iter().copied())).unwrap().subcommand
+}
+
+fn middleware<'a>() -> SubcommandsMiddleware<'a, Context, String> {
+    SubcommandsMiddleware::new()
+        .before("admin user", |path, ctx: &mut Context| {
+            ctx.events.push(format!("before admin user: {}", path.join(" ")));
+            Ok(())
+        })
+        .before("admin", |_, ctx: &mut Context| {
+            ctx.events.push("before admin".to_string());
+            ctx.authenticated.then_some(()).ok_or_else(|| "not authenticated".to_string())
+        })
+        .before("", |_, ctx: &mut Context| {
+            ctx.events.push("before root".to_string());
+            Ok(())
+        })
+        .after("", |_, ctx: &mut Context| {
+            ctx.events.push("after root".to_string());
+            Ok(())
+        })
+        .after("admin", |_, ctx: &mut Context| {
+            ctx.events.push("after admin".to_string());
+            Ok(())
+        })
+}
+
+#[test]
+fn hooks_run_root_to_leaf() {
+    let mut ctx = Context { authenticated: true, ..Context::default() };
+    let output = parse(&["admin", "user", "list"]).dispatch_with(&mut middleware(), &mut ctx, &mut App);
+    assert_eq!(output, Ok("list"));
+    assert_eq!(ctx.events, vec![
+        "before root",
+        "before admin",
+        "before admin user: admin user list",
+        "after admin",
+        "after root",
+    ]);
+}
+
+#[test]
+fn hooks_only_match_prefix() {
+    let mut ctx = Context::default();
+    let output = parse(&["db", "migrate"]).dispatch_with(&mut middleware(), &mut ctx, &mut App);
+    assert_eq!(output, Ok("migrate"));
+    assert_eq!(ctx.events, vec!["before root", "after root"]);
+}
+
+#[test]
+fn before_hook_stops_dispatch() {
+    let mut ctx = Context::default();
+    let output = parse(&["admin", "user", "add", "bob"]).dispatch_with(&mut middleware(), &mut ctx, &mut App);
+    assert_eq!(output, Err("not authenticated".to_string()));
+    assert_eq!(ctx.events, vec!["before root", "before admin"]);
+}
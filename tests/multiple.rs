@@ -17,6 +17,7 @@ mod sub2 {
     use clap_trie::clap_subcommand;
     clap_subcommand!{
         #[derive(Debug)]
+        #[allow(clippy::enum_variant_names)]
         enum Other {
             #[derive(Debug)] "list other" => { pub(crate) id: String },
             #[derive(Debug)] "get other" => { pub(crate) id: String },
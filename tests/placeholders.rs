@@ -0,0 +1,228 @@
+mod common;
+
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use clap_trie::clap_trie;
+use common::render;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Level {
+        Low,
+        High,
+    }
+
+    impl std::str::FromStr for Level {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "low" => Ok(Self::Low),
+                "high" => Ok(Self::High),
+                _ => Err(format!("unknown level `{}`", s)),
+            }
+        }
+    }
+
+    impl std::fmt::Display for Level {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(match self {
+                Self::Low => "low",
+                Self::High => "high",
+            })
+        }
+    }
+
+    clap_subcommand!{
+        #[derive(Debug)]
+        #[trie(to_args)]
+        enum Things {
+            /// Get information about a kind of thing
+            #[derive(Debug)] "get {kind} info" => {
+                /// Kind of thing
+                pub(crate) kind: String,
+                #[arg(long)] pub(crate) verbose: bool,
+            },
+            #[derive(Debug)] "get {kind} count" => {},
+            #[derive(Debug)] "get person" => { pub(crate) id: String },
+            #[derive(Debug)]
+            #[trie(also = "put {level} limit")]
+            "set {level} limit" => { pub(crate) max: u32, pub(crate) level: Level },
+            #[derive(Debug)] "move {item} to {place} now" => {},
+        }
+    }
+}
+
+mod flattened {
+    use clap_trie::clap_subcommand;
+
+    #[derive(Debug, clap::Args)]
+    pub struct Output {
+        #[arg(long)] pub(crate) json: bool,
+    }
+
+    clap_subcommand!{
+        #[derive(Debug)]
+        enum Shows {
+            #[derive(Debug)] "show {kind} details" => { #[command(flatten)] pub(crate) output: Output, #[arg(long)] pub(crate) limit: Option<u32> },
+        }
+    }
+}
+
+clap_trie!{
+    #[derive(Debug)]
+    #[trie(repl, markdown)]
+    enum Eager {
+        sub::Things
+    }
+}
+
+clap_trie!{
+    #[derive(Debug)]
+    #[trie(lazy)]
+    enum Lazy {
+        sub::Things
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name="test")]
+struct EagerCli {
+    #[clap(subcommand)]
+    subcommand: Eager,
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name="test")]
+struct LazyCli {
+    #[clap(subcommand)]
+    subcommand: Lazy,
+}
+
+fn parse(args: &[&str]) -> Result<sub::Things, clap::Error> {
+    let eager = EagerCli::try_parse_from(std::iter::once("test").chain(args.iter().copied())).map(|cli| match cli.subcommand {
+        Eager::Things(things) => things,
+    });
+    let lazy = LazyCli::try_parse_from(std::iter::once("test").chain(args.iter().copied())).map(|cli| match cli.subcommand {
+        Lazy::Things(things) => things,
+    });
+    match (&eager, &lazy) {
+        (Ok(eager), Ok(lazy)) => assert_eq!(format!("{:?}", lazy), format!("{:?}", eager)),
+        (Err(eager), Err(lazy)) => assert_eq!(lazy.kind(), eager.kind()),
+        _ => panic!("eager and lazy parses differ: {:?} {:?}", eager, lazy),
+    }
+    eager
+}
+
+#[test]
+fn parse_values() {
+    assert!(matches!(parse(&["get", "user", "info"]).unwrap(), sub::Things::GetKindInfo(sub::GetKindInfoCmd { kind, verbose: false }) if kind == "user"));
+    assert!(matches!(parse(&["get", "group", "info", "--verbose"]).unwrap(), sub::Things::GetKindInfo(sub::GetKindInfoCmd { kind, verbose: true }) if kind == "group"));
+    assert!(matches!(parse(&["get", "group", "count"]).unwrap(), sub::Things::GetKindCount(sub::GetKindCountCmd { kind }) if kind == "group"));
+    assert!(matches!(parse(&["set", "high", "limit", "3"]).unwrap(), sub::Things::SetLevelLimit(sub::SetLevelLimitCmd { max: 3, level: sub::Level::High })));
+    assert!(matches!(parse(&["put", "low", "limit", "3"]).unwrap(), sub::Things::SetLevelLimit(sub::SetLevelLimitCmd { level: sub::Level::Low, .. })));
+    assert!(matches!(parse(&["move", "box", "to", "attic", "now"]).unwrap(), sub::Things::MoveItemToPlaceNow(sub::MoveItemToPlaceNowCmd { item, place }) if item == "box" && place == "attic"));
+}
+
+#[test]
+fn literals_take_precedence() {
+    assert!(matches!(parse(&["get", "person", "info"]).unwrap(), sub::Things::GetPerson(sub::GetPersonCmd { id }) if id == "info"));
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(parse(&["get", "info"]).unwrap_err().kind(), ErrorKind::MissingRequiredArgument);
+    assert_eq!(parse(&["get", "user", "person", "x"]).unwrap_err().kind(), ErrorKind::UnknownArgument);
+    assert_eq!(parse(&["get", "user"]).unwrap_err().kind(), ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand);
+    assert_eq!(parse(&["get", "user", "list"]).unwrap_err().kind(), ErrorKind::UnknownArgument);
+
+    let err = parse(&["set", "medium", "limit", "3"]).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ValueValidation);
+    assert!(err.to_string().contains("unknown level `medium`"));
+}
+
+#[test]
+fn help() {
+    let mut cmd = EagerCli::command();
+    cmd.build();
+    let get = cmd.find_subcommand_mut("get").unwrap();
+    let help = get.render_help().to_string();
+    assert!(help.contains("Usage: test get [KIND] [COMMAND]"));
+    assert!(help.contains("[KIND]  Kind of thing"));
+    let names = get.get_subcommands().map(|cmd| cmd.get_name()).collect::<Vec<_>>();
+    assert_eq!(names, ["person", "count", "info", "help"]);
+
+    let info = get.find_subcommand_mut("info").unwrap();
+    assert_eq!(info.get_about().map(|about| about.to_string()).as_deref(), Some("Get information about a kind of thing"));
+    assert!(info.get_arguments().all(|arg| arg.get_id() != "kind"));
+}
+
+#[test]
+fn standalone() {
+    let cmd = sub::SetLevelLimitCmd::try_parse_from(["limit", "high", "3"]).unwrap();
+    assert_eq!((cmd.level, cmd.max), (sub::Level::High, 3));
+    assert_eq!(sub::SetLevelLimitCmd::try_parse_from(["limit", "3"]).unwrap_err().kind(), ErrorKind::ValueValidation);
+
+    let mut cmd = sub::GetKindInfoCmd::try_parse_from(["info", "user"]).unwrap();
+    cmd.try_update_from(["info", "--verbose"]).unwrap();
+    assert_eq!((cmd.kind.as_str(), cmd.verbose), ("user", true));
+    cmd.try_update_from(["info", "group", "--verbose"]).unwrap();
+    assert_eq!((cmd.kind.as_str(), cmd.verbose), ("group", true));
+
+    let mut cmd = flattened::ShowKindDetailsCmd::try_parse_from(["details", "user", "--limit", "2"]).unwrap();
+    cmd.try_update_from(["details", "--json"]).unwrap();
+    assert_eq!((cmd.kind.as_str(), cmd.output.json, cmd.limit), ("user", true, Some(2)));
+}
+
+#[test]
+fn commands_match_eager() {
+    let (mut eager, mut lazy) = (Vec::new(), Vec::new());
+    render(&mut EagerCli::command(), "test", &mut eager);
+    render(&mut LazyCli::command(), "test", &mut lazy);
+    assert_eq!(lazy, eager);
+}
+
+#[test]
+fn to_args() {
+    for args in [&["get", "user", "info", "--verbose"][..], &["set", "low", "limit", "3"], &["move", "box", "to", "attic", "now"]] {
+        let things = parse(args).unwrap();
+        assert_eq!(things.to_args(), args);
+        assert_eq!(Eager::Things(things).to_string(), args.join(" "));
+    }
+    assert_eq!(parse(&["put", "low", "limit", "3"]).unwrap().to_args(), ["set", "low", "limit", "3"]);
+}
+
+#[test]
+fn introspection() {
+    let command = parse(&["get", "user", "info"]).unwrap();
+    assert_eq!(Eager::Things(command).info().path, ["get", "{kind}", "info"]);
+
+    let info = |path: &[&str]| Eager::lookup_path(path).map(|id| id.info().path);
+    assert_eq!(info(&["get", "user", "info"]), Some(&["get", "{kind}", "info"][..]));
+    assert_eq!(info(&["put", "low", "limit"]), Some(&["set", "{level}", "limit"][..]));
+    assert_eq!(info(&["move", "box", "to", "attic", "now"]), Some(&["move", "{item}", "to", "{place}", "now"][..]));
+    assert_eq!(info(&["get", "person"]), Some(&["get", "person"][..]));
+    assert_eq!(info(&["get", "person", "info"]), None);
+    assert_eq!(info(&["get", "info", "info"]), None);
+    assert_eq!(info(&["get", "user"]), None);
+
+    let prefix = |args: &[&str]| Eager::longest_prefix(args).map(|(len, info)| (len, info.path));
+    assert_eq!(prefix(&["get", "user", "info", "x"]), Some((3, &["get", "{kind}", "info"][..])));
+    assert_eq!(prefix(&["get", "person", "info"]), Some((2, &["get", "person"][..])));
+}
+
+#[test]
+fn complete() {
+    assert_eq!(Eager::complete("get "), ["person"]);
+    assert_eq!(Eager::complete("get user "), ["count", "info"]);
+    assert_eq!(Eager::complete("get user i"), ["info"]);
+    assert_eq!(Eager::complete("move box to attic "), ["now"]);
+}
+
+#[test]
+fn markdown() {
+    let markdown = Eager::generate_markdown(EagerCli::command());
+    assert!(markdown.contains("  - `test get {kind}`\n    - [`test get {kind} count`](#test-get-kind-count)\n    - [`test get {kind} info`](#test-get-kind-info)\n"));
+    assert!(markdown.contains("## test get {kind} info\n\nGet information about a kind of thing\n"));
+}
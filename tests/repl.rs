@@ -0,0 +1,89 @@
+use clap_trie::clap_trie;
+
+mod sub {
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        enum Thingies {
+            "list thingy" => {},
+            "get thingy" => { pub(crate) id: String },
+            "get thingy attributes" => { pub(crate) id: String },
+            "get other" => {},
+        }
+    }
+}
+
+clap_trie!{
+    #[trie(repl)]
+    enum Subcommands {
+        sub::Thingies,
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    calls: Vec<String>,
+}
+
+impl Handler for Recorder {
+    type Output = ();
+
+    fn list_thingy(&mut self, _cmd: sub::ListThingyCmd) {
+        self.calls.push("list thingy".to_string());
+    }
+
+    fn get_thingy(&mut self, cmd: sub::GetThingyCmd) {
+        self.calls.push(format!("get thingy {}", cmd.id));
+    }
+
+    fn get_thingy_attributes(&mut self, cmd: sub::GetThingyAttributesCmd) {
+        self.calls.push(format!("get thingy attributes {}", cmd.id));
+    }
+
+    fn get_other(&mut self, _cmd: sub::GetOtherCmd) {
+        self.calls.push("get other".to_string());
+    }
+}
+
+#[test]
+fn repl_with_script() {
+    let mut script = vec![
+        "get thingy 'my thingy'\n",
+        "\n",
+        "bogus command\n",
+        "get thingy \"unterminated\n",
+        "get\n",
+        "--help\n",
+        "get thingy attributes a\\ b\n",
+        "list thingy\n",
+    ].into_iter();
+
+    let mut handler = Recorder::default();
+    let mut prompts = 0;
+    Subcommands::repl_with("> ", &mut handler, |prompt| {
+        assert_eq!(prompt, "> ");
+        prompts += 1;
+        script.next().map(str::to_string)
+    });
+
+    assert_eq!(prompts, 9);
+    assert_eq!(handler.calls, vec!["get thingy my thingy", "get thingy attributes a b", "list thingy"]);
+}
+
+#[test]
+fn split_line() {
+    assert_eq!(Subcommands::split_line("get  thingy\t'a b'"), Some(vec!["get".into(), "thingy".into(), "a b".into()]));
+    assert_eq!(Subcommands::split_line(r#"a"b c"d "\"" \\ ''"#), Some(vec!["ab cd".into(), "\"".into(), "\\".into(), "".into()]));
+    assert_eq!(Subcommands::split_line("  "), Some(vec![]));
+    assert_eq!(Subcommands::split_line("get 'thingy"), None);
+}
+
+#[test]
+fn complete() {
+    assert_eq!(Subcommands::complete(""), vec!["get", "list"]);
+    assert_eq!(Subcommands::complete("g"), vec!["get"]);
+    assert_eq!(Subcommands::complete("get "), vec!["other", "thingy"]);
+    assert_eq!(Subcommands::complete("get t"), vec!["thingy"]);
+    assert_eq!(Subcommands::complete("get thingy "), vec!["attributes"]);
+    assert_eq!(Subcommands::complete("get thingy attributes "), Vec::<String>::new());
+    assert_eq!(Subcommands::complete("bogus "), Vec::<String>::new());
+}
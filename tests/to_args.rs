@@ -0,0 +1,187 @@
+use std::{ffi::OsString, path::PathBuf};
+
+use clap::Parser;
+use clap_trie::clap_trie;
+
+mod sub {
+    use std::path::PathBuf;
+
+    use clap_trie::clap_subcommand;
+    clap_subcommand!{
+        #[derive(Debug, PartialEq)]
+        #[trie(to_args)]
+        enum Thingies {
+            #[derive(Debug, PartialEq)]
+            "list thingy" => {
+                #[arg(short, long)]
+                pub(crate) all: bool,
+                #[arg(short, action = clap::ArgAction::Count)]
+                pub(crate) verbose: u8,
+                #[arg(long = "tag")]
+                pub(crate) tags: Vec<String>,
+            },
+            #[derive(Debug, PartialEq)]
+            "get thingy" => {
+                pub(crate) id: String,
+                #[arg(long)]
+                pub(crate) output: Option<PathBuf>,
+                #[arg(long)]
+                pub(crate) limit: Option<u32>,
+            },
+            #[derive(Debug, PartialEq)]
+            "get thingy attributes" => {
+                pub(crate) ids: Vec<String>,
+            },
+        }
+    }
+}
+
+clap_trie!{
+    #[derive(Debug, PartialEq)]
+    enum Subcommands {
+        sub::Thingies
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name="test")]
+struct Cli {
+    #[clap(subcommand)]
+    subcommand: Subcommands,
+}
+
+fn round_trip(command: &Subcommands) -> Subcommands {
+    let args = std::iter::once(OsString::from("test")).chain(command.to_args());
+    Cli::try_parse_from(args).unwrap().subcommand
+}
+
+#[test]
+fn to_args() {
+    let command = Subcommands::Thingies(sub::Thingies::GetThingy(sub::GetThingyCmd {
+        id: "my thingy".to_string(),
+        output: Some(PathBuf::from("out.json")),
+        limit: None,
+    }));
+    assert_eq!(command.to_args(), ["get", "thingy", "--output=out.json", "my thingy"].map(OsString::from));
+    assert_eq!(command.to_string(), "get thingy --output=out.json 'my thingy'");
+}
+
+#[test]
+fn round_trips() {
+    let commands = [
+        Subcommands::Thingies(sub::Thingies::ListThingy(sub::ListThingyCmd { all: true, verbose: 2, tags: vec!["a".into(), "b c".into()] })),
+        Subcommands::Thingies(sub::Thingies::ListThingy(sub::ListThingyCmd { all: false, verbose: 0, tags: vec![] })),
+        Subcommands::Thingies(sub::Thingies::GetThingy(sub::GetThingyCmd { id: "-1".into(), output: None, limit: Some(3) })),
+        Subcommands::Thingies(sub::Thingies::GetThingy(sub::GetThingyCmd { id: "x".into(), output: Some("-x".into()), limit: None })),
+        Subcommands::Thingies(sub::Thingies::ListThingy(sub::ListThingyCmd { all: false, verbose: 0, tags: vec!["-a".into(), "--b".into()] })),
+        Subcommands::Thingies(sub::Thingies::GetThingyAttributes(sub::GetThingyAttributesCmd { ids: vec!["a".into(), "it's".into()] })),
+    ];
+    for command in commands {
+        assert_eq!(round_trip(&command), command);
+    }
+}
+
+#[test]
+fn display() {
+    let command = sub::Thingies::ListThingy(sub::ListThingyCmd { all: true, verbose: 1, tags: vec!["it's".into()] });
+    assert_eq!(command.to_string(), r#"list thingy --all -v '--tag=it'\''s'"#);
+    assert_eq!(Subcommands::Thingies(command).to_string(), r#"list thingy --all -v '--tag=it'\''s'"#);
+}
+
+mod formats {
+    use std::ffi::OsString;
+
+    use clap_trie::clap_subcommand;
+
+    // Neither implements `Display`, nor does `Common` have a `to_args` unless it's asked for
+    #[derive(Clone, Debug, PartialEq, clap::ValueEnum)]
+    pub enum Format {
+        Json,
+        PlainText,
+    }
+
+    #[derive(Debug, PartialEq, clap::Args)]
+    pub struct Common {
+        #[arg(long)]
+        pub(crate) dry_run: bool,
+    }
+
+    impl Common {
+        pub fn to_args(&self) -> Vec<OsString> {
+            self.dry_run.then(|| OsString::from("--dry-run")).into_iter().collect()
+        }
+    }
+
+    clap_subcommand!{
+        #[derive(Debug, PartialEq)]
+        enum Plain {
+            #[derive(Debug, PartialEq)]
+            "show thingy" => {
+                #[command(flatten)]
+                pub(crate) common: Common,
+                #[arg(long, value_enum)]
+                pub(crate) format: Option<Format>,
+            },
+        }
+    }
+
+    clap_subcommand!{
+        #[derive(Debug, PartialEq)]
+        #[trie(to_args)]
+        enum Exported {
+            #[derive(Debug, PartialEq)]
+            "export thingy" => {
+                #[command(flatten)]
+                pub(crate) common: Common,
+                #[arg(long, value_enum)]
+                pub(crate) format: Format,
+            },
+        }
+    }
+}
+
+clap_trie!{
+    #[derive(Debug, PartialEq)]
+    enum Mixed {
+        formats::Plain,
+        formats::Exported,
+    }
+}
+
+clap_trie!{
+    #[derive(Debug, PartialEq)]
+    enum Exports {
+        formats::Exported,
+    }
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name="test")]
+struct MixedCli {
+    #[clap(subcommand)]
+    subcommand: Mixed,
+}
+
+#[derive(Debug, clap::Parser)]
+#[command(name="test")]
+struct ExportsCli {
+    #[clap(subcommand)]
+    subcommand: Exports,
+}
+
+#[test]
+fn opt_in() {
+    let cli = MixedCli::try_parse_from(["test", "show", "thingy", "--dry-run", "--format", "plain-text"]).unwrap();
+    assert_eq!(cli.subcommand, Mixed::Plain(formats::Plain::ShowThingy(formats::ShowThingyCmd {
+        common: formats::Common { dry_run: true },
+        format: Some(formats::Format::PlainText),
+    })));
+
+    let command = Exports::Exported(formats::Exported::ExportThingy(formats::ExportThingyCmd {
+        common: formats::Common { dry_run: true },
+        format: formats::Format::PlainText,
+    }));
+    assert_eq!(command.to_string(), "export thingy --dry-run --format=plain-text");
+    let args = std::iter::once(OsString::from("test")).chain(command.to_args());
+    assert_eq!(ExportsCli::try_parse_from(args).unwrap().subcommand, command);
+}
@@ -0,0 +1,6 @@
+// Errors reported while the macros expand, compared against the `.stderr` files next to each case
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
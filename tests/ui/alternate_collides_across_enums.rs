@@ -0,0 +1,25 @@
+mod thingies {
+    clap_trie::clap_subcommand!{
+        enum Thingies {
+            "list thingy" => {},
+        }
+    }
+}
+
+mod legacy {
+    clap_trie::clap_subcommand!{
+        enum Legacy {
+            #[trie(also = "list thingy")]
+            "show thingy" => {},
+        }
+    }
+}
+
+clap_trie::clap_trie!{
+    enum Subcommands {
+        thingies::Thingies,
+        legacy::Legacy,
+    }
+}
+
+fn main() {}
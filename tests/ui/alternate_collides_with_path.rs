@@ -0,0 +1,9 @@
+clap_trie::clap_subcommand!{
+    enum Thingies {
+        "list thingy" => {},
+        #[trie(also = "list thingy")]
+        "show thingy" => {},
+    }
+}
+
+fn main() {}
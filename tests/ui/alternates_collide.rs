@@ -0,0 +1,10 @@
+clap_trie::clap_subcommand!{
+    enum Thingies {
+        #[trie(also = "ls")]
+        "list thingy" => {},
+        #[trie(also = "ls")]
+        "list thingies" => {},
+    }
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+mod people {
+    clap_trie::clap_subcommand!{
+        enum People {
+            "get person" => {},
+        }
+    }
+}
+
+mod legacy {
+    clap_trie::clap_subcommand!{
+        enum Legacy {
+            "get-person" => {},
+        }
+    }
+}
+
+clap_trie::clap_trie!{
+    #[trie(handler)]
+    enum Subcommands {
+        people::People,
+        legacy::Legacy,
+    }
+}
+
+fn main() {}
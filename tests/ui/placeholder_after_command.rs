@@ -0,0 +1,14 @@
+clap_trie::clap_subcommand!{
+    enum Things {
+        "get" => {},
+        "get {kind} info" => {},
+    }
+}
+
+clap_trie::clap_trie!{
+    enum Subcommands {
+        Things,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+clap_trie::clap_subcommand!{
+    enum Things {
+        "get {kind} info" => {
+            #[arg(value_parser = ["user", "group"])]
+            kind: String,
+        },
+    }
+}
+
+fn main() {}
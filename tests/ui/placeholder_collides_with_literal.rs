@@ -0,0 +1,14 @@
+clap_trie::clap_subcommand!{
+    enum Things {
+        "get {kind} info" => {},
+        "get info" => {},
+    }
+}
+
+clap_trie::clap_trie!{
+    enum Subcommands {
+        Things,
+    }
+}
+
+fn main() {}
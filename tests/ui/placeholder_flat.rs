@@ -0,0 +1,14 @@
+clap_trie::clap_subcommand!{
+    enum Things {
+        "get {kind} info" => {},
+    }
+}
+
+clap_trie::clap_trie!{
+    #[trie(flat)]
+    enum Subcommands {
+        Things,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+clap_trie::clap_subcommand!{
+    enum Things {
+        "get {kind}" => {},
+    }
+}
+
+fn main() {}
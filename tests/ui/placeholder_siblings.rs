@@ -0,0 +1,14 @@
+clap_trie::clap_subcommand!{
+    enum Things {
+        "get {kind} info" => {},
+        "get {name} count" => {},
+    }
+}
+
+clap_trie::clap_trie!{
+    enum Subcommands {
+        Things,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use clap_trie::clap_subcommand;
+
+clap_subcommand!{
+    enum Modules {
+        "super" => {},
+    }
+}
+
+fn main() {}
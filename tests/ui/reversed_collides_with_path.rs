@@ -0,0 +1,9 @@
+clap_trie::clap_subcommand!{
+    #[trie(reversed)]
+    enum People {
+        "get person" => {},
+        "person get" => {},
+    }
+}
+
+fn main() {}